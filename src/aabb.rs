@@ -5,7 +5,8 @@
 //! for any axis.
 
 
-use cgmath::Point3;
+use std::fmt::{Display, Formatter};
+use cgmath::{EuclideanSpace, InnerSpace, Matrix4, Point3, Transform as CgTransform, Vector3};
 
 
 // local min/max funcs for f32 since it isn't Ord and doesn't work with std::min/max
@@ -13,9 +14,42 @@ fn float_min(a: f32, b: f32) -> f32 { if a < b { a } else { b } }
 fn float_max(a: f32, b: f32) -> f32 { if a > b { a } else { b } }
 
 
+/// One of the three coordinate axes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Axis { X, Y, Z }
+
+impl Axis {
+    /// Returns the index of this axis in a point or vector, i.e. 0 for X, 1 for Y, 2 for Z.
+    pub fn index(self) -> usize {
+        match self {
+            Axis::X => 0,
+            Axis::Y => 1,
+            Axis::Z => 2,
+        }
+    }
+}
+
+
+/// A ray with an origin and a direction. The direction doesn't need to be normalized, but any
+/// distances along the ray (`t` values) are measured in multiples of its length.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Ray {
+    pub origin: Point3<f32>,
+    pub direction: Vector3<f32>,
+}
+
+impl Ray {
+    pub fn new(origin: Point3<f32>, direction: Vector3<f32>) -> Ray { Ray { origin, direction } }
+
+    /// Returns the point at distance `t` along the ray.
+    pub fn at(&self, t: f32) -> Point3<f32> { self.origin + self.direction * t }
+}
+
+
 /// An axis-aligned bounding box. Represented by a cuboid defined by two points. As long as the
 /// `set_*` functions are used, the `lower` point will be less than or equal to the `upper` point
 /// for any axis.
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct AABB {
     lower: Point3<f32>,
     upper: Point3<f32>,
@@ -38,6 +72,82 @@ impl AABB {
         AABB { lower, upper }
     }
 
+    /// Constructs a new AABB from six floats laid out as `[lower.x, lower.y, lower.z, upper.x,
+    /// upper.y, upper.z]`, the same layout produced by `as_floats`. Like `from`, this method does
+    /// not ensure `lower` <= `upper` for all axes.
+    pub fn from_floats(floats: [f32; 6]) -> AABB {
+        let [lx, ly, lz, ux, uy, uz] = floats;
+        AABB { lower: Point3::new(lx, ly, lz), upper: Point3::new(ux, uy, uz) }
+    }
+
+    /// Constructs the smallest AABB containing all of the given points. Returns a zero-sized AABB
+    /// at the origin if `points` is empty.
+    pub fn from_points(points: &[Point3<f32>]) -> AABB {
+        points.iter().copied().collect()
+    }
+
+    /// Returns the smallest AABB containing both this AABB and `other`.
+    pub fn union(&self, other: &AABB) -> AABB {
+        AABB {
+            lower: Point3::new(float_min(self.lower.x, other.lower.x), float_min(self.lower.y, other.lower.y), float_min(self.lower.z, other.lower.z)),
+            upper: Point3::new(float_max(self.upper.x, other.upper.x), float_max(self.upper.y, other.upper.y), float_max(self.upper.z, other.upper.z)),
+        }
+    }
+
+    /// Returns the eight corners of the AABB. The first corner is `lower` and the last is `upper`,
+    /// with bit 0, 1, and 2 of the index selecting the upper x, y, and z coordinate respectively.
+    pub fn corners(&self) -> [Point3<f32>; 8] {
+        let (l, u) = (self.lower, self.upper);
+        [
+            Point3::new(l.x, l.y, l.z),
+            Point3::new(u.x, l.y, l.z),
+            Point3::new(l.x, u.y, l.z),
+            Point3::new(u.x, u.y, l.z),
+            Point3::new(l.x, l.y, u.z),
+            Point3::new(u.x, l.y, u.z),
+            Point3::new(l.x, u.y, u.z),
+            Point3::new(u.x, u.y, u.z),
+        ]
+    }
+
+    /// Returns the corner farthest along `dir` (the support point used by GJK and similar
+    /// algorithms). On axes where `dir` is zero, the `lower` coordinate is used.
+    pub fn support(&self, dir: Vector3<f32>) -> Point3<f32> {
+        Point3::new(
+            if dir.x > 0.0 { self.upper.x } else { self.lower.x },
+            if dir.y > 0.0 { self.upper.y } else { self.lower.y },
+            if dir.z > 0.0 { self.upper.z } else { self.lower.z },
+        )
+    }
+
+    /// Returns the corner farthest against the plane normal `n`, i.e. the corner with the smallest
+    /// signed distance to any plane with that normal. On axes where `n` is zero, the `upper`
+    /// coordinate is used.
+    pub fn closest_corner_to_plane(&self, n: Vector3<f32>) -> Point3<f32> {
+        Point3::new(
+            if n.x > 0.0 { self.lower.x } else { self.upper.x },
+            if n.y > 0.0 { self.lower.y } else { self.upper.y },
+            if n.z > 0.0 { self.lower.z } else { self.upper.z },
+        )
+    }
+
+    /// Transforms the corners of this AABB by the given matrix and returns the AABB enclosing them.
+    pub fn transformed(&self, matrix: Matrix4<f32>) -> AABB {
+        AABB::from_points(&self.corners().map(|c| matrix.transform_point(c)))
+    }
+
+    /// Returns the AABB as six floats laid out as `[lower.x, lower.y, lower.z, upper.x, upper.y,
+    /// upper.z]`. Useful for uploading to GPU buffers.
+    pub fn as_floats(&self) -> [f32; 6] {
+        [self.lower.x, self.lower.y, self.lower.z, self.upper.x, self.upper.y, self.upper.z]
+    }
+
+    /// Returns true if every coordinate of this AABB is within `epsilon` of the matching coordinate
+    /// of `other`. Useful for comparing computed boxes that accumulate float error.
+    pub fn approx_eq(&self, other: &AABB, epsilon: f32) -> bool {
+        self.as_floats().iter().zip(other.as_floats()).all(|(a, b)| (a - b).abs() <= epsilon)
+    }
+
     /// Returns the length of the AABB in the x dimension.
     pub fn size_x(&self) -> f32 { self.upper.x - self.lower.x }
     /// Returns the length of the AABB in the y dimension.
@@ -59,6 +169,148 @@ impl AABB {
     pub fn back(&self) -> f32 { self.upper.z }
 
 
+    /// Returns the center and radius of the smallest sphere enclosing this AABB. The radius is half
+    /// the length of the diagonal.
+    pub fn bounding_sphere(&self) -> (Point3<f32>, f32) {
+        let center = self.lower.midpoint(self.upper);
+        (center, (self.upper - self.lower).magnitude() * 0.5)
+    }
+
+    /// Returns true if the sphere with the given center and radius lies entirely within this AABB.
+    pub fn contains_sphere(&self, center: Point3<f32>, radius: f32) -> bool {
+        (0..3).all(|i| center[i] - radius >= self.lower[i] && center[i] + radius <= self.upper[i])
+    }
+
+    /// Returns the bounds swept by this AABB as it moves by `velocity`, i.e. the union of the box at
+    /// its start and end positions. Useful as a broad-phase bound for continuous collision.
+    pub fn swept(&self, velocity: Vector3<f32>) -> AABB {
+        self.union(&AABB { lower: self.lower + velocity, upper: self.upper + velocity })
+    }
+
+    /// Returns a copy of this AABB expanded outwards by `margin` on every side. A negative margin
+    /// shrinks the box instead; any axis shrunk past zero size collapses to its center.
+    pub fn inflated(&self, margin: f32) -> AABB {
+        let mut lower = self.lower;
+        let mut upper = self.upper;
+        for i in 0..3 {
+            lower[i] -= margin;
+            upper[i] += margin;
+            if lower[i] > upper[i] {
+                let center = (self.lower[i] + self.upper[i]) * 0.5;
+                lower[i] = center;
+                upper[i] = center;
+            }
+        }
+        AABB { lower, upper }
+    }
+
+    /// Splits the AABB into two by a plane perpendicular to `axis` at `position`, returning the
+    /// lower and upper halves. `position` is clamped into the range of the box along that axis.
+    /// Boxes with `lower` above `upper` on some axis are treated as if their corners were swapped.
+    pub fn split(&self, axis: Axis, position: f32) -> (AABB, AABB) {
+        let (mut lower, mut upper) = (self.lower, self.upper);
+        for j in 0..3 {
+            lower[j] = float_min(self.lower[j], self.upper[j]);
+            upper[j] = float_max(self.lower[j], self.upper[j]);
+        }
+        let i = axis.index();
+        let position = float_max(lower[i], float_min(position, upper[i]));
+        let mut first_upper = upper;
+        first_upper[i] = position;
+        let mut second_lower = lower;
+        second_lower[i] = position;
+        (AABB::from(lower, first_upper), AABB::from(second_lower, upper))
+    }
+
+    /// Returns up to six non-overlapping boxes that together cover the part of this AABB that isn't
+    /// inside `other`. Returns a copy of this AABB if they don't overlap, and nothing if `other`
+    /// covers it completely. Boxes that only touch along a face are treated as not overlapping.
+    pub fn difference(&self, other: &AABB) -> Vec<AABB> {
+        // the overlapping region, clamped to this box
+        let mut cut_lower = self.lower;
+        let mut cut_upper = self.upper;
+        for i in 0..3 {
+            cut_lower[i] = float_max(self.lower[i], other.lower[i]);
+            cut_upper[i] = float_min(self.upper[i], other.upper[i]);
+            if cut_lower[i] >= cut_upper[i] { return vec![*self] }
+        }
+
+        // peel off slabs on either side of the overlap one axis at a time, shrinking the remaining
+        // region to the overlap's extent on each axis once it's been handled
+        let mut pieces = Vec::with_capacity(6);
+        let (mut lower, mut upper) = (self.lower, self.upper);
+        for i in 0..3 {
+            if lower[i] < cut_lower[i] {
+                let mut slab_upper = upper;
+                slab_upper[i] = cut_lower[i];
+                pieces.push(AABB { lower, upper: slab_upper });
+            }
+            if cut_upper[i] < upper[i] {
+                let mut slab_lower = lower;
+                slab_lower[i] = cut_upper[i];
+                pieces.push(AABB { lower: slab_lower, upper });
+            }
+            lower[i] = cut_lower[i];
+            upper[i] = cut_upper[i];
+        }
+        pieces
+    }
+
+    /// Tests a ray against this AABB. If the ray hits, returns the distances along the ray at which
+    /// it enters and exits the box as `(t_enter, t_exit)`. If the ray starts inside the box,
+    /// `t_enter` is zero. Hits behind the ray origin are ignored.
+    pub fn ray_intersect(&self, ray: Ray) -> Option<(f32, f32)> {
+        let mut t_enter = 0.0f32;
+        let mut t_exit = f32::INFINITY;
+        for axis in 0..3 {
+            let inv_dir = 1.0 / ray.direction[axis];
+            let t1 = (self.lower[axis] - ray.origin[axis]) * inv_dir;
+            let t2 = (self.upper[axis] - ray.origin[axis]) * inv_dir;
+            t_enter = t_enter.max(t1.min(t2));
+            t_exit = t_exit.min(t1.max(t2));
+        }
+        if t_enter <= t_exit { Some((t_enter, t_exit)) } else { None }
+    }
+
+    /// Returns the coordinates of every voxel (cubes of side `voxel_size`, aligned to the origin) the
+    /// ray passes through inside this AABB, in order along the ray, using the Amanatides-Woo
+    /// algorithm. Voxel `(i, j, k)` spans from `(i, j, k) * voxel_size` to `(i+1, j+1, k+1) * voxel_size`.
+    /// Yields nothing if the ray misses the box, or if `voxel_size` isn't finite and positive.
+    pub fn voxel_traversal(&self, ray: Ray, voxel_size: f32) -> impl Iterator<Item = (i32, i32, i32)> {
+        let mut traversal = VoxelTraversal {
+            voxel: [0; 3],
+            step: [0; 3],
+            t_max: [f32::INFINITY; 3],
+            t_delta: [f32::INFINITY; 3],
+            t_exit: 0.0,
+            done: true,
+        };
+        if !(voxel_size.is_finite() && voxel_size > 0.0) { return traversal }
+        if let Some((t_enter, t_exit)) = self.ray_intersect(ray) {
+            let start = ray.at(t_enter);
+            for axis in 0..3 {
+                // keep the starting voxel inside the box, in case the entry point is on its far side
+                let first = (self.lower[axis] / voxel_size).floor() as i32;
+                let last = ((self.upper[axis] / voxel_size).ceil() as i32).saturating_sub(1).max(first);
+                let voxel = ((start[axis] / voxel_size).floor() as i32).clamp(first, last);
+                traversal.voxel[axis] = voxel;
+
+                let dir = ray.direction[axis];
+                if dir != 0.0 {
+                    let step = if dir > 0.0 { 1 } else { -1 };
+                    let boundary = (voxel + if dir > 0.0 { 1 } else { 0 }) as f32 * voxel_size;
+                    traversal.step[axis] = step;
+                    traversal.t_max[axis] = (boundary - ray.origin[axis]) / dir;
+                    traversal.t_delta[axis] = voxel_size / dir.abs();
+                }
+            }
+            traversal.t_exit = t_exit;
+            traversal.done = false;
+        }
+        traversal
+    }
+
+
     /// Updates the lower point. Rearranges the coordinates to assure that `lower` <= `upper` for
     /// all axes.
     pub fn set_lower(&mut self, lower: Point3<f32>) {
@@ -127,6 +379,55 @@ impl AABB {
     }
 }
 
+// iterator state for `AABB::voxel_traversal`
+struct VoxelTraversal {
+    voxel: [i32; 3],
+    step: [i32; 3],
+    // distance along the ray at which it crosses the next voxel boundary on each axis
+    t_max: [f32; 3],
+    // distance along the ray between voxel boundaries on each axis
+    t_delta: [f32; 3],
+    t_exit: f32,
+    done: bool,
+}
+
+impl Iterator for VoxelTraversal {
+    type Item = (i32, i32, i32);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done { return None }
+        let current = (self.voxel[0], self.voxel[1], self.voxel[2]);
+
+        let axis = if self.t_max[0] < self.t_max[1] {
+            if self.t_max[0] < self.t_max[2] { 0 } else { 2 }
+        }
+        else if self.t_max[1] < self.t_max[2] { 1 } else { 2 };
+        if self.t_max[axis] >= self.t_exit {
+            // the ray leaves the box before reaching the next voxel
+            self.done = true;
+        }
+        else {
+            self.voxel[axis] += self.step[axis];
+            self.t_max[axis] += self.t_delta[axis];
+        }
+        Some(current)
+    }
+}
+
+/// Finds the nearest box hit by the given ray. Returns the index of the box and the distance along
+/// the ray at which it was hit. If multiple boxes are hit at the same distance, the lowest index wins.
+pub fn ray_pick(ray: Ray, boxes: &[AABB]) -> Option<(usize, f32)> {
+    let mut nearest: Option<(usize, f32)> = None;
+    for (i, b) in boxes.iter().enumerate() {
+        if let Some((t, _)) = b.ray_intersect(ray) {
+            if nearest.is_none_or(|(_, nearest_t)| t < nearest_t) {
+                nearest = Some((i, t));
+            }
+        }
+    }
+    nearest
+}
+
 impl Default for AABB {
     fn default() -> Self {
         AABB {
@@ -136,10 +437,34 @@ impl Default for AABB {
     }
 }
 
+/// Prints the corners and size, e.g. `AABB(lower: (1.0, 2.0, 3.0), upper: (4.0, 5.0, 6.0)) size (3.0, 3.0, 3.0)`.
+impl Display for AABB {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let (l, u) = (self.lower, self.upper);
+        write!(f, "AABB(lower: ({:?}, {:?}, {:?}), upper: ({:?}, {:?}, {:?})) size ({:?}, {:?}, {:?})",
+               l.x, l.y, l.z, u.x, u.y, u.z, self.size_x(), self.size_y(), self.size_z())
+    }
+}
+
+/// Collects the union of all the boxes. An empty iterator produces a zero-sized AABB at the origin.
+impl FromIterator<AABB> for AABB {
+    fn from_iter<I: IntoIterator<Item = AABB>>(iter: I) -> Self {
+        iter.into_iter().reduce(|a, b| a.union(&b)).unwrap_or_default()
+    }
+}
+
+/// Collects the smallest AABB containing all the points. An empty iterator produces a zero-sized
+/// AABB at the origin.
+impl FromIterator<Point3<f32>> for AABB {
+    fn from_iter<I: IntoIterator<Item = Point3<f32>>>(iter: I) -> Self {
+        iter.into_iter().map(|p| AABB { lower: p, upper: p }).collect()
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::AABB;
-    use cgmath::Point3;
+    use super::{ray_pick, Axis, Ray, AABB};
+    use cgmath::{Point3, Vector3};
 
     macro_rules! assert_eq_float {
         ($a:expr, $b:expr) => { assert!((($a) - ($b)).abs() < std::f32::EPSILON) }
@@ -157,4 +482,275 @@ mod tests {
         assert_eq_float!(b.front(), 5.0); // note that these are in the wrong order
         assert_eq_float!(b.back(), 2.0); // AABB::from() does not check point ordering
     }
+
+    #[test]
+    fn test_aabb_from_points_and_corners() {
+        let b = AABB::from_points(&[Point3::new(1.0, 5.0, -1.0), Point3::new(-2.0, 0.0, 3.0), Point3::new(0.0, 7.0, 0.0)]);
+        assert_eq!(b.as_floats(), [-2.0, 0.0, -1.0, 1.0, 7.0, 3.0]);
+        let corners = b.corners();
+        assert_eq!(corners[0], b.lower);
+        assert_eq!(corners[7], b.upper);
+        assert_eq!(AABB::from_points(&corners).as_floats(), b.as_floats());
+        assert_eq!(AABB::from_points(&[]).as_floats(), [0.0; 6]);
+    }
+
+    #[test]
+    fn test_aabb_support() {
+        let b = AABB::from(Point3::new(-1.0, -2.0, -3.0), Point3::new(1.0, 2.0, 3.0));
+        assert_eq!(b.support(Vector3::new(1.0, 0.0, 0.0)).x, 1.0);
+        assert_eq!(b.support(Vector3::new(-1.0, 0.0, 0.0)).x, -1.0);
+        assert_eq!(b.support(Vector3::new(1.0, -1.0, 1.0)), Point3::new(1.0, -2.0, 3.0));
+        // the support point maximizes the dot product over all corners
+        let dir = Vector3::new(0.3, -0.7, 0.2);
+        let best = b.corners().iter().map(|c| dir.x * c.x + dir.y * c.y + dir.z * c.z).fold(f32::MIN, f32::max);
+        let s = b.support(dir);
+        assert_eq_float!(dir.x * s.x + dir.y * s.y + dir.z * s.z, best);
+
+        assert_eq!(b.closest_corner_to_plane(Vector3::new(1.0, -1.0, 1.0)), Point3::new(-1.0, 2.0, -3.0));
+        assert_eq!(b.closest_corner_to_plane(dir), b.support(-dir));
+    }
+
+    #[test]
+    fn test_aabb_floats_round_trip() {
+        let b = AABB::from(Point3::new(1.0, 2.0, 3.0), Point3::new(4.0, 5.0, 6.0));
+        let floats = b.as_floats();
+        assert_eq!(floats, [1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+        let b2 = AABB::from_floats(floats);
+        assert_eq!(b2.lower, b.lower);
+        assert_eq!(b2.upper, b.upper);
+    }
+
+    #[test]
+    fn test_ray_intersect() {
+        let b = AABB::from(Point3::new(2.0, -1.0, -1.0), Point3::new(4.0, 1.0, 1.0));
+        let ray = Ray::new(Point3::new(0.0, 0.0, 0.0), Vector3::new(1.0, 0.0, 0.0));
+        assert_eq!(b.ray_intersect(ray), Some((2.0, 4.0)));
+        let miss = Ray::new(Point3::new(0.0, 5.0, 0.0), Vector3::new(1.0, 0.0, 0.0));
+        assert_eq!(b.ray_intersect(miss), None);
+        let behind = Ray::new(Point3::new(0.0, 0.0, 0.0), Vector3::new(-1.0, 0.0, 0.0));
+        assert_eq!(b.ray_intersect(behind), None);
+        let inside = Ray::new(Point3::new(3.0, 0.0, 0.0), Vector3::new(1.0, 0.0, 0.0));
+        assert_eq!(b.ray_intersect(inside), Some((0.0, 1.0)));
+    }
+
+    #[test]
+    fn test_ray_pick() {
+        let boxes = [
+            AABB::from(Point3::new(5.0, -1.0, -1.0), Point3::new(6.0, 1.0, 1.0)),
+            AABB::from(Point3::new(2.0, 5.0, -1.0), Point3::new(3.0, 6.0, 1.0)), // off to the side, missed
+            AABB::from(Point3::new(2.0, -1.0, -1.0), Point3::new(3.0, 1.0, 1.0)),
+            AABB::from(Point3::new(2.0, -2.0, -2.0), Point3::new(3.0, 2.0, 2.0)), // tied with index 2
+        ];
+        let ray = Ray::new(Point3::new(0.0, 0.0, 0.0), Vector3::new(1.0, 0.0, 0.0));
+        assert_eq!(ray_pick(ray, &boxes), Some((2, 2.0)));
+        assert_eq!(ray_pick(ray, &boxes[..2]), Some((0, 5.0)));
+        assert_eq!(ray_pick(ray, &[]), None);
+    }
+
+    #[test]
+    fn test_aabb_split() {
+        let unit = AABB::from(Point3::new(0.0, 0.0, 0.0), Point3::new(1.0, 1.0, 1.0));
+        let (a, b) = unit.split(Axis::X, 0.5);
+        assert_eq!(a.as_floats(), [0.0, 0.0, 0.0, 0.5, 1.0, 1.0]);
+        assert_eq!(b.as_floats(), [0.5, 0.0, 0.0, 1.0, 1.0, 1.0]);
+        let (a, b) = unit.split(Axis::Y, 0.5);
+        assert_eq!(a.as_floats(), [0.0, 0.0, 0.0, 1.0, 0.5, 1.0]);
+        assert_eq!(b.as_floats(), [0.0, 0.5, 0.0, 1.0, 1.0, 1.0]);
+        let (a, b) = unit.split(Axis::Z, 0.5);
+        assert_eq!(a.as_floats(), [0.0, 0.0, 0.0, 1.0, 1.0, 0.5]);
+        assert_eq!(b.as_floats(), [0.0, 0.0, 0.5, 1.0, 1.0, 1.0]);
+    }
+
+    #[test]
+    fn test_aabb_split_clamps_position() {
+        let unit = AABB::from(Point3::new(0.0, 0.0, 0.0), Point3::new(1.0, 1.0, 1.0));
+        let (a, b) = unit.split(Axis::X, 2.0);
+        assert_eq!(a.as_floats(), unit.as_floats());
+        assert_eq_float!(b.size_x(), 0.0);
+
+        // inverted boxes from `AABB::from` are split as if normalized
+        let inverted = AABB::from(Point3::new(1.0, 1.0, 1.0), Point3::new(0.0, 0.0, 0.0));
+        let (a, b) = inverted.split(Axis::X, 0.5);
+        assert_eq!(a.as_floats(), [0.0, 0.0, 0.0, 0.5, 1.0, 1.0]);
+        assert_eq!(b.as_floats(), [0.5, 0.0, 0.0, 1.0, 1.0, 1.0]);
+        let (a, _) = inverted.split(Axis::Y, -3.0);
+        assert_eq_float!(a.size_y(), 0.0);
+    }
+
+    #[test]
+    fn test_aabb_bounding_sphere() {
+        let unit = AABB::from(Point3::new(0.0, 0.0, 0.0), Point3::new(1.0, 1.0, 1.0));
+        let (center, radius) = unit.bounding_sphere();
+        assert_eq!(center, Point3::new(0.5, 0.5, 0.5));
+        assert_eq_float!(radius, 3.0f32.sqrt() / 2.0);
+    }
+
+    #[test]
+    fn test_aabb_contains_sphere() {
+        let unit = AABB::from(Point3::new(0.0, 0.0, 0.0), Point3::new(1.0, 1.0, 1.0));
+        assert!(unit.contains_sphere(Point3::new(0.5, 0.5, 0.5), 0.5));
+        assert!(unit.contains_sphere(Point3::new(0.3, 0.5, 0.5), 0.2));
+        assert!(!unit.contains_sphere(Point3::new(0.3, 0.5, 0.5), 0.4));
+        assert!(!unit.contains_sphere(Point3::new(2.0, 0.5, 0.5), 0.1));
+    }
+
+    #[test]
+    fn test_aabb_inflated() {
+        let b = AABB::from(Point3::new(0.0, 0.0, 0.0), Point3::new(1.0, 2.0, 3.0));
+        let inflated = b.inflated(0.5);
+        assert_eq_float!(inflated.size_x(), 2.0);
+        assert_eq_float!(inflated.size_y(), 3.0);
+        assert_eq_float!(inflated.size_z(), 4.0);
+        let shrunk = b.inflated(-0.25);
+        assert_eq_float!(shrunk.size_x(), 0.5);
+        assert_eq_float!(shrunk.size_z(), 2.5);
+    }
+
+    #[test]
+    fn test_aabb_display() {
+        let b = AABB::from(Point3::new(1.0, 2.0, 3.0), Point3::new(4.0, 5.0, 6.5));
+        let text = b.to_string();
+        assert_eq!(text, "AABB(lower: (1.0, 2.0, 3.0), upper: (4.0, 5.0, 6.5)) size (3.0, 3.0, 3.5)");
+        assert!(text.contains("size (3.0, 3.0, 3.5)"));
+    }
+
+    #[test]
+    fn test_aabb_swept() {
+        let b = AABB::from(Point3::new(0.0, 1.0, 2.0), Point3::new(1.0, 2.0, 3.0));
+        assert_eq!(b.swept(Vector3::new(0.0, 0.0, 0.0)), b);
+        assert_eq!(b.swept(Vector3::new(2.5, 0.0, 0.0)).as_floats(), [0.0, 1.0, 2.0, 3.5, 2.0, 3.0]);
+        assert_eq!(b.swept(Vector3::new(-1.0, 0.5, -4.0)).as_floats(), [-1.0, 1.0, -2.0, 1.0, 2.5, 3.0]);
+    }
+
+    #[test]
+    fn test_aabb_inflated_over_shrink_clamps() {
+        let b = AABB::from(Point3::new(0.0, 0.0, 0.0), Point3::new(1.0, 2.0, 3.0));
+        let shrunk = b.inflated(-10.0);
+        assert_eq!(shrunk.as_floats(), [0.5, 1.0, 1.5, 0.5, 1.0, 1.5]);
+        // only axes that flip are clamped
+        let partial = b.inflated(-0.75);
+        assert_eq!(partial.as_floats(), [0.5, 0.75, 0.75, 0.5, 1.25, 2.25]);
+    }
+
+    #[test]
+    fn test_voxel_traversal_axis_aligned() {
+        let b = AABB::from(Point3::new(0.0, 0.0, 0.0), Point3::new(4.0, 4.0, 4.0));
+        let ray = Ray::new(Point3::new(-1.0, 1.5, 2.5), Vector3::new(1.0, 0.0, 0.0));
+        let voxels: Vec<_> = b.voxel_traversal(ray, 1.0).collect();
+        assert_eq!(voxels, vec![(0, 1, 2), (1, 1, 2), (2, 1, 2), (3, 1, 2)]);
+
+        let ray = Ray::new(Point3::new(1.0, 1.0, 10.0), Vector3::new(0.0, 0.0, -2.0));
+        let voxels: Vec<_> = b.voxel_traversal(ray, 2.0).collect();
+        assert_eq!(voxels, vec![(0, 0, 1), (0, 0, 0)]);
+
+        let miss = Ray::new(Point3::new(-1.0, 5.0, 0.0), Vector3::new(1.0, 0.0, 0.0));
+        assert_eq!(b.voxel_traversal(miss, 1.0).count(), 0);
+    }
+
+    #[test]
+    fn test_voxel_traversal_invalid_size() {
+        let b = AABB::from(Point3::new(0.0, 0.0, 0.0), Point3::new(1.0, 1.0, 1.0));
+        let ray = Ray::new(Point3::new(-1.0, 0.5, 0.5), Vector3::new(1.0, 0.0, 0.0));
+        for size in [0.0, -1.0, f32::NAN, f32::INFINITY] {
+            assert_eq!(b.voxel_traversal(ray, size).take(10).count(), 0, "voxel size {}", size);
+        }
+        // tiny voxels saturate the coordinates instead of overflowing
+        let far = AABB::from(Point3::new(-1.0e30, 0.0, 0.0), Point3::new(-1.0e29, 1.0, 1.0));
+        let ray = Ray::new(Point3::new(-2.0e30, 0.5, 0.5), Vector3::new(1.0, 0.0, 0.0));
+        assert!(far.voxel_traversal(ray, 1.0).next().is_some());
+    }
+
+    #[test]
+    fn test_voxel_traversal_diagonal() {
+        let b = AABB::from(Point3::new(-2.0, -2.0, -2.0), Point3::new(2.0, 2.0, 2.0));
+        let ray = Ray::new(Point3::new(-3.0, -2.5, -2.2), Vector3::new(1.0, 0.9, 0.8));
+        let voxels: Vec<_> = b.voxel_traversal(ray, 0.5).collect();
+        // enters through the x = -2 face at (-2, -1.6, -1.4)
+        assert_eq!(voxels[0], (-4, -4, -3));
+        // each step moves to a face-adjacent voxel
+        for pair in voxels.windows(2) {
+            let (a, b) = (pair[0], pair[1]);
+            assert_eq!((b.0 - a.0).abs() + (b.1 - a.1).abs() + (b.2 - a.2).abs(), 1);
+        }
+        let last = voxels[voxels.len() - 1];
+        assert!(last.0 == 3 || last.1 == 3 || last.2 == 3);
+    }
+
+    #[test]
+    fn test_aabb_collect() {
+        let boxes = vec![
+            AABB::from(Point3::new(0.0, 0.0, 0.0), Point3::new(1.0, 1.0, 1.0)),
+            AABB::from(Point3::new(-2.0, 0.5, 0.0), Point3::new(0.0, 3.0, 0.5)),
+            AABB::from(Point3::new(0.0, -1.0, 4.0), Point3::new(0.5, 0.0, 5.0)),
+        ];
+        let bounds: AABB = boxes.into_iter().collect();
+        assert_eq!(bounds.as_floats(), [-2.0, -1.0, 0.0, 1.0, 3.0, 5.0]);
+
+        let empty: AABB = Vec::<AABB>::new().into_iter().collect();
+        assert_eq!(empty.as_floats(), [0.0; 6]);
+
+        let points: AABB = [Point3::new(1.0, 2.0, 3.0), Point3::new(-1.0, 5.0, 0.0)].into_iter().collect();
+        assert_eq!(points.as_floats(), [-1.0, 2.0, 0.0, 1.0, 5.0, 3.0]);
+    }
+
+    fn volume(b: &AABB) -> f32 { b.size_x() * b.size_y() * b.size_z() }
+
+    fn overlap_volume(a: &AABB, b: &AABB) -> f32 {
+        let [al, bl] = [a.as_floats(), b.as_floats()];
+        (0..3).map(|i| (al[i + 3].min(bl[i + 3]) - al[i].max(bl[i])).max(0.0)).product()
+    }
+
+    #[test]
+    fn test_aabb_difference_partial() {
+        let a = AABB::from(Point3::new(0.0, 0.0, 0.0), Point3::new(4.0, 4.0, 4.0));
+        // overlaps a 1x2x4 column through the middle of `a`, and sticks out the top and bottom in z
+        let b = AABB::from(Point3::new(1.0, 1.0, -1.0), Point3::new(2.0, 3.0, 5.0));
+        let pieces = a.difference(&b);
+        assert_eq!(pieces.len(), 4);
+
+        // the pieces tile exactly the part of `a` outside `b`
+        let total: f32 = pieces.iter().map(volume).sum();
+        assert_eq_float!(total, volume(&a) - overlap_volume(&a, &b));
+        for (i, p) in pieces.iter().enumerate() {
+            assert_eq_float!(overlap_volume(p, &a), volume(p));
+            assert_eq_float!(overlap_volume(p, &b), 0.0);
+            for q in &pieces[i + 1..] {
+                assert_eq_float!(overlap_volume(p, q), 0.0);
+            }
+        }
+
+        // a corner overlap leaves three pieces
+        let corner = AABB::from(Point3::new(3.0, 3.0, 3.0), Point3::new(5.0, 5.0, 5.0));
+        let pieces = a.difference(&corner);
+        assert_eq!(pieces.len(), 3);
+        assert_eq_float!(pieces.iter().map(volume).sum::<f32>(), 63.0);
+
+        // a hole in the middle needs all six
+        let hole = AABB::from(Point3::new(1.0, 1.0, 1.0), Point3::new(2.0, 2.0, 2.0));
+        assert_eq!(a.difference(&hole).len(), 6);
+    }
+
+    #[test]
+    fn test_aabb_difference_disjoint_and_covered() {
+        let a = AABB::from(Point3::new(0.0, 0.0, 0.0), Point3::new(1.0, 1.0, 1.0));
+        let far = AABB::from(Point3::new(5.0, 5.0, 5.0), Point3::new(6.0, 6.0, 6.0));
+        assert_eq!(a.difference(&far), vec![a]);
+        let touching = AABB::from(Point3::new(1.0, 0.0, 0.0), Point3::new(2.0, 1.0, 1.0));
+        assert_eq!(a.difference(&touching), vec![a]);
+
+        let cover = AABB::from(Point3::new(-1.0, -1.0, -1.0), Point3::new(2.0, 2.0, 2.0));
+        assert!(a.difference(&cover).is_empty());
+        assert!(a.difference(&a).is_empty());
+    }
+
+    #[test]
+    fn test_aabb_approx_eq() {
+        let epsilon = 0.001;
+        let b = AABB::from(Point3::new(0.0, 0.0, 0.0), Point3::new(1.0, 2.0, 3.0));
+        let close = AABB::from(Point3::new(epsilon / 2.0, 0.0, 0.0), Point3::new(1.0, 2.0, 3.0 - epsilon / 2.0));
+        let far = AABB::from(Point3::new(0.0, 0.0, 0.0), Point3::new(1.0, 2.0 + epsilon * 2.0, 3.0));
+        assert!(b.approx_eq(&close, epsilon));
+        assert!(close.approx_eq(&b, epsilon));
+        assert!(!b.approx_eq(&far, epsilon));
+    }
 }
@@ -1,72 +1,138 @@
 #![allow(dead_code)]
 
 use std::borrow::Borrow;
-use std::cell::{Ref, RefCell, RefMut};
+use std::cell::{Cell, OnceCell, Ref, RefCell, RefMut};
 use std::fmt::{Debug, Formatter};
 use std::ops::Deref;
+#[cfg(debug_assertions)]
+use std::panic::Location;
 use std::rc::Rc;
 
 
 #[repr(transparent)]
-/// Just a wrapper around Rc<RefCell<T>> to make using it a bit cleaner.
-pub struct SimpleCell<T: ?Sized>(Rc<RefCell<T>>);
+/// Just a wrapper around an `Rc`-shared `RefCell<T>` to make using it a bit cleaner.
+///
+/// In debug builds, the cell also remembers where it was most recently borrowed through `get` or
+/// `get_mut`, and includes that location in the panic message when a borrow conflicts.
+pub struct SimpleCell<T: ?Sized>(Rc<CellInner<T>>);
+
+struct CellInner<T: ?Sized> {
+    #[cfg(debug_assertions)]
+    borrowed_at: Cell<Option<&'static Location<'static>>>,
+    value: RefCell<T>,
+}
+
+impl<T> CellInner<T> {
+    fn new(value: T) -> Self {
+        CellInner {
+            #[cfg(debug_assertions)]
+            borrowed_at: Cell::new(None),
+            value: RefCell::new(value),
+        }
+    }
+}
 
 impl<T> SimpleCell<T> {
-    pub fn new(value: T) -> Self { SimpleCell(Rc::new(RefCell::new(value))) }
+    pub fn new(value: T) -> Self { SimpleCell(Rc::new(CellInner::new(value))) }
 
-    pub fn replace(&self, value: T) -> T { self.0.replace(value) }
+    pub fn replace(&self, value: T) -> T { self.0.value.replace(value) }
+
+    /// Overwrites the contained value, dropping the old one.
+    pub fn set(&self, value: T) { self.0.value.replace(value); }
 
     pub fn try_unwrap(self) -> Result<T, SimpleCell<T>> {
         match Rc::try_unwrap(self.0) {
-            Ok(refcell) => Ok(refcell.into_inner()),
+            Ok(inner) => Ok(inner.value.into_inner()),
             Err(rc) => Err(SimpleCell(rc))
         }
     }
 
     pub fn replace_with<F>(&self, f: F) -> T where F: FnOnce(&mut T) -> T {
-        self.0.replace_with(f)
+        self.0.value.replace_with(f)
     }
 
-    pub fn swap(&self, other: &SimpleCell<T>) { self.0.swap(other.0.borrow()) }
+    pub fn swap(&self, other: &SimpleCell<T>) { self.0.value.swap(&other.0.value) }
 
+    #[cfg_attr(debug_assertions, track_caller)]
     pub fn get(&self) -> Ref<T> {
-        RefCell::borrow(Rc::borrow(&self.0))
+        #[cfg(debug_assertions)]
+        {
+            let borrow = self.0.value.try_borrow().unwrap_or_else(|_| self.borrow_conflict("already mutably borrowed"));
+            self.0.borrowed_at.set(Some(Location::caller()));
+            borrow
+        }
+        #[cfg(not(debug_assertions))]
+        RefCell::borrow(&self.0.value)
+    }
+
+    /// Returns a clone of the contained value. The borrow is released before returning, so the
+    /// result can be freely used alongside other borrows of this cell.
+    #[cfg_attr(debug_assertions, track_caller)]
+    pub fn get_cloned(&self) -> T where T: Clone {
+        self.get().clone()
     }
 
     pub fn try_get(&self) -> Result<Ref<T>, std::cell::BorrowError> {
-        self.0.try_borrow()
+        self.0.value.try_borrow()
     }
 
+    #[cfg_attr(debug_assertions, track_caller)]
     pub fn get_mut(&self) -> RefMut<T> {
-        RefCell::borrow_mut(&self.0)
+        #[cfg(debug_assertions)]
+        {
+            let borrow = self.0.value.try_borrow_mut().unwrap_or_else(|_| self.borrow_conflict("already borrowed"));
+            self.0.borrowed_at.set(Some(Location::caller()));
+            borrow
+        }
+        #[cfg(not(debug_assertions))]
+        RefCell::borrow_mut(&self.0.value)
     }
 
     pub fn try_get_mut(&self) -> Result<RefMut<T>, std::cell::BorrowMutError> {
-        self.0.try_borrow_mut()
+        self.0.value.try_borrow_mut()
     }
 
-    pub fn as_ptr(&self) -> *mut T { self.0.as_ptr() }
+    pub fn as_ptr(&self) -> *mut T { self.0.value.as_ptr() }
 
     pub fn clone(&self) -> Self {
         SimpleCell(self.0.clone())
     }
+
+    #[cfg(debug_assertions)]
+    #[track_caller]
+    fn borrow_conflict(&self, reason: &str) -> ! {
+        match self.0.borrowed_at.get() {
+            Some(location) => panic!("SimpleCell<{}> {}: most recently borrowed at {}", std::any::type_name::<T>(), reason, location),
+            None => panic!("SimpleCell<{}> {}", std::any::type_name::<T>(), reason),
+        }
+    }
 }
 
 impl<T: Clone + ?Sized> SimpleCell<T> {
     fn boxed(value: &T) -> SimpleCell<Box<T>> {
-        SimpleCell(Rc::new(RefCell::new(Box::new(value.clone()))))
+        SimpleCell(Rc::new(CellInner::new(Box::new(value.clone()))))
     }
 }
 
 impl<T: Default> SimpleCell<T> {
-    pub fn take(&self) -> T { self.0.take() }
+    pub fn take(&self) -> T { self.0.value.take() }
+}
+
+impl<T: Copy> SimpleCell<T> {
+    /// Reads the value, transforms it with `f`, and writes the result back. The value is copied
+    /// out before calling `f`, so no borrow is held while it runs.
+    #[cfg_attr(debug_assertions, track_caller)]
+    pub fn update(&self, f: impl FnOnce(T) -> T) {
+        let value = *self.get();
+        *self.get_mut() = f(value);
+    }
 }
 
 impl<T> Deref for SimpleCell<T> {
     type Target = RefCell<T>;
 
     fn deref(&self) -> &Self::Target {
-        Rc::borrow(&self.0)
+        &self.0.value
     }
 }
 
@@ -82,7 +148,7 @@ impl<T: Debug> Debug for SimpleCell<T> {
 
 impl<T> From<T> for SimpleCell<T> {
     fn from(t: T) -> Self {
-        SimpleCell(Rc::from(RefCell::from(t)))
+        SimpleCell(Rc::new(CellInner::new(t)))
     }
 }
 
@@ -93,3 +159,98 @@ impl<T: PartialEq + ?Sized> PartialEq<SimpleCell<T>> for SimpleCell<T> {
 }
 
 impl<T: Eq> Eq for SimpleCell<T> {}
+
+
+
+/// A single-threaded, lazily-initialized value. The init function is called on the first `get`,
+/// and the result is stored for all later calls. This is the cheaper, non-thread-safe counterpart
+/// to `InitOnce`.
+///
+/// ```
+/// # use toolbelt::cell::LazyCell;
+/// let lazy = LazyCell::new(|| 2 + 2);
+/// assert_eq!(*lazy.get(), 4);
+/// ```
+pub struct LazyCell<T, F = fn() -> T> {
+    value: OnceCell<T>,
+    init: Cell<Option<F>>,
+}
+
+impl<T, F: FnOnce() -> T> LazyCell<T, F> {
+    /// Creates a new LazyCell which will call `init` on first access.
+    pub const fn new(init: F) -> Self {
+        LazyCell { value: OnceCell::new(), init: Cell::new(Some(init)) }
+    }
+
+    /// Returns a reference to the value, initializing it first if this is the first access.
+    /// Panics if called re-entrantly from within the init function.
+    pub fn get(&self) -> &T {
+        self.value.get_or_init(|| match self.init.take() {
+            Some(init) => init(),
+            None => panic!("LazyCell<{}> accessed during its own initialization", std::any::type_name::<T>())
+        })
+    }
+}
+
+impl<T: Debug, F> Debug for LazyCell<T, F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.value.get() {
+            Some(value) => f.debug_tuple("LazyCell").field(value).finish(),
+            None => f.write_str("LazyCell(<uninitialized>)")
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::{LazyCell, SimpleCell};
+
+    #[test]
+    fn test_get_cloned() {
+        let cell = SimpleCell::new(vec![1, 2, 3]);
+        let copy = cell.get_cloned();
+        // the borrow from get_cloned must already be released
+        cell.get_mut().push(4);
+        assert_eq!(copy, vec![1, 2, 3]);
+        assert_eq!(*cell.get(), vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_set() {
+        let cell = SimpleCell::new(1);
+        let other = cell.clone();
+        cell.set(5);
+        assert_eq!(*cell.get(), 5);
+        assert_eq!(*other.get(), 5);
+    }
+
+    #[test]
+    fn test_update() {
+        let cell = SimpleCell::new(0i32);
+        for _ in 0..10 {
+            cell.update(|x| x + 1);
+        }
+        assert_eq!(*cell.get(), 10);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "already borrowed: most recently borrowed at src/cell.rs:")]
+    fn test_borrow_conflict_location() {
+        let cell = SimpleCell::new(1);
+        let _first = cell.get();
+        let _second = cell.get_mut();
+    }
+
+    #[test]
+    fn test_lazy_cell_inits_once() {
+        let calls = std::cell::Cell::new(0);
+        let lazy = LazyCell::new(|| { calls.set(calls.get() + 1); 42 });
+        assert_eq!(calls.get(), 0);
+        assert_eq!(*lazy.get(), 42);
+        assert_eq!(*lazy.get(), 42);
+        assert_eq!(*lazy.get(), 42);
+        assert_eq!(calls.get(), 1);
+    }
+}
@@ -1,14 +1,38 @@
 //! Type for converting between color spaces. Still WIP and probably not totally correct or reliable.
 
 use std::ops::{Index, IndexMut, Range};
+use cgmath::{Matrix3, Matrix4, Vector3, Vector4};
 use serde_derive::{Serialize, Deserialize};
-use crate::{slice_max, slice_min};
+use crate::{lerp, slice_max, slice_min};
+use crate::curve::FloatCurve;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ColorSpace {
     RGB, RGBA, HSL, HSLA, HSV, HSVA, Lab, LabA,
 }
 
+impl ColorSpace {
+    /// Returns the variant of this space without alpha, e.g. `RGBA` -> `RGB`.
+    pub const fn base(self) -> ColorSpace {
+        match self {
+            ColorSpace::RGB | ColorSpace::RGBA => ColorSpace::RGB,
+            ColorSpace::HSL | ColorSpace::HSLA => ColorSpace::HSL,
+            ColorSpace::HSV | ColorSpace::HSVA => ColorSpace::HSV,
+            ColorSpace::Lab | ColorSpace::LabA => ColorSpace::Lab,
+        }
+    }
+
+    /// Returns the variant of this space with alpha, e.g. `RGB` -> `RGBA`.
+    pub const fn with_alpha(self) -> ColorSpace {
+        match self {
+            ColorSpace::RGB | ColorSpace::RGBA => ColorSpace::RGBA,
+            ColorSpace::HSL | ColorSpace::HSLA => ColorSpace::HSLA,
+            ColorSpace::HSV | ColorSpace::HSVA => ColorSpace::HSVA,
+            ColorSpace::Lab | ColorSpace::LabA => ColorSpace::LabA,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct Color {
     components: [f32; 4],
@@ -32,6 +56,54 @@ macro_rules! const_color_fn {
     }
 }
 
+macro_rules! color_builder {
+    ($(#[$attr:meta])* $builder:ident, $ctor:ident => $c0:ident, $c1:ident, $c2:ident => $space:ident, $space_a:ident) => {
+        $(#[$attr])*
+        #[derive(Debug, Clone, Copy, Default, PartialEq)]
+        pub struct $builder {
+            $c0: f32,
+            $c1: f32,
+            $c2: f32,
+            alpha: Option<f32>,
+        }
+
+        impl $builder {
+            pub fn $c0(self, $c0: f32) -> Self { Self { $c0, ..self } }
+            pub fn $c1(self, $c1: f32) -> Self { Self { $c1, ..self } }
+            pub fn $c2(self, $c2: f32) -> Self { Self { $c2, ..self } }
+            pub fn a(self, alpha: f32) -> Self { Self { alpha: Some(alpha), ..self } }
+
+            /// Builds the color. If an alpha value was set the color uses the alpha variant of
+            /// the color space.
+            pub fn build(self) -> Color {
+                match self.alpha {
+                    Some(alpha) => Color { components: [self.$c0, self.$c1, self.$c2, alpha], space: ColorSpace::$space_a },
+                    None => Color { components: [self.$c0, self.$c1, self.$c2, 1.0], space: ColorSpace::$space },
+                }
+            }
+        }
+
+        impl Color {
+            /// Returns a builder for specifying a color by named channels. Unset channels default to
+            /// 0.0 and alpha to 1.0.
+            pub fn $ctor() -> $builder { $builder::default() }
+        }
+    }
+}
+
+color_builder! {
+    /// Builder for RGB(A) colors, see `Color::rgb_builder`.
+    RgbBuilder, rgb_builder => r, g, b => RGB, RGBA
+}
+color_builder! {
+    /// Builder for HSL(A) colors, see `Color::hsl_builder`.
+    HslBuilder, hsl_builder => h, s, l => HSL, HSLA
+}
+color_builder! {
+    /// Builder for HSV(A) colors, see `Color::hsv_builder`.
+    HsvBuilder, hsv_builder => h, s, v => HSV, HSVA
+}
+
 
 //#[rustversion::attr(nightly, feature(split_array))]
 impl Color {
@@ -47,14 +119,19 @@ impl Color {
     pub const fn from_lab (l: f32, a: f32, b: f32) -> Color { Color { components: [l, a, b, 1.0], space: ColorSpace::Lab } }
     pub const fn from_laba(l: f32, a: f32, b: f32, alpha: f32) -> Color { Color { components: [l, a, b, alpha], space: ColorSpace::LabA } }
 
+    /// Returns a copy of this color with the given alpha. The space is switched to its alpha
+    /// variant (e.g. `RGB` -> `RGBA`) so the alpha is reported by `check_alpha`.
     pub fn with_alpha(self, alpha: f32) -> Color {
         let [a, b, c, _] = self.components;
-        Self { components: [a, b, c, alpha], ..self }
+        Self { components: [a, b, c, alpha], space: self.space.with_alpha() }
     }
 
+    /// Same as `with_alpha(1.0)`.
     pub fn opaque(self) -> Color { self.with_alpha(1.0) }
+    /// Same as `with_alpha(0.0)`.
     pub fn transparent(self) -> Color { self.with_alpha(0.0) }
 
+    /// Returns the alpha if this color's space has an alpha channel, or `None` otherwise.
     pub fn check_alpha(self) -> Option<f32> {
         match self.space {
             ColorSpace::RGB | ColorSpace::HSL | ColorSpace::HSV | ColorSpace::Lab => None,
@@ -64,9 +141,64 @@ impl Color {
 
     pub fn alpha(&self) -> f32 { self.components[3] }
 
+    /// Returns the ColorSpace this color's components are stored in.
+    pub fn space(&self) -> ColorSpace { self.space }
+
+    /// Returns the red channel, converting to RGB first if necessary.
+    pub fn r(&self) -> f32 { self.to_rgb().components[0] }
+    /// Returns the green channel, converting to RGB first if necessary.
+    pub fn g(&self) -> f32 { self.to_rgb().components[1] }
+    /// Returns the blue channel, converting to RGB first if necessary.
+    pub fn b(&self) -> f32 { self.to_rgb().components[2] }
+    /// Returns the alpha channel. Same as `alpha`.
+    pub fn a(&self) -> f32 { self.components[3] }
+
+    /// Returns the RGBA components reordered by `pattern`, e.g. `[2, 1, 0, 3]` gives BGRA.
+    /// Panics if any index in `pattern` is greater than 3.
+    pub fn swizzle(&self, pattern: [usize; 4]) -> [f32; 4] {
+        let rgba = self.to_rgb().components;
+        pattern.map(|i| {
+            if i > 3 { panic!("Swizzle index {} out of bounds for RGBA", i) }
+            rgba[i]
+        })
+    }
+
+    /// Returns the color as `[r, g, b, a]` bytes, converting to RGB first. Components are clamped
+    /// to `[0, 1]` and scaled to `[0, 255]`.
     pub fn as_bytes(self) -> [u8; 4] {
-        let [a, b, c, d] = self.components;
-        [(a*256.0).floor() as u8, (b*256.0).floor() as u8, (c*256.0).floor() as u8, (d*256.0).floor() as u8]
+        let [r, g, b, a] = self.to_rgb().components;
+        let to_byte = |v: f32| (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+        [to_byte(r), to_byte(g), to_byte(b), to_byte(a)]
+    }
+
+    /// Constructs an RGBA color from `[r, g, b, a]` bytes, the inverse of `as_bytes`.
+    pub fn from_bytes(bytes: [u8; 4]) -> Color {
+        let [r, g, b, a] = bytes.map(|byte| byte as f32 / 255.0);
+        Color::from_rgba(r, g, b, a)
+    }
+
+    /// Packs the color into a `u32` as `0xAARRGGBB`, i.e. alpha in the most significant byte and
+    /// blue in the least significant byte. See `as_bytes`.
+    pub fn to_u32_argb(&self) -> u32 {
+        let [r, g, b, a] = self.as_bytes();
+        u32::from_be_bytes([a, r, g, b])
+    }
+
+    /// Packs the color into a `u32` as `0xRRGGBBAA`, i.e. red in the most significant byte and
+    /// alpha in the least significant byte. See `as_bytes`.
+    pub fn to_u32_rgba(&self) -> u32 {
+        u32::from_be_bytes(self.as_bytes())
+    }
+
+    /// Unpacks an RGBA color from a `u32` laid out as `0xAARRGGBB`, the inverse of `to_u32_argb`.
+    pub fn from_u32_argb(packed: u32) -> Color {
+        let [a, r, g, b] = packed.to_be_bytes();
+        Color::from_bytes([r, g, b, a])
+    }
+
+    /// Unpacks an RGBA color from a `u32` laid out as `0xRRGGBBAA`, the inverse of `to_u32_rgba`.
+    pub fn from_u32_rgba(packed: u32) -> Color {
+        Color::from_bytes(packed.to_be_bytes())
     }
 
     // stable: unsafe implementation
@@ -104,7 +236,7 @@ impl Color {
                 let h_prime = hue * 6.0;
                 let x = chroma * (1.0 - (h_prime % 2.0 - 1.0).abs());
 
-                let (r1, b1, g1) = {
+                let (r1, g1, b1) = {
                     if      h_prime <  1.0 { (chroma, x, 0.0) }
                     else if h_prime <  2.0 { (x, chroma, 0.0) }
                     else if h_prime <  3.0 { (0.0, chroma, x) }
@@ -123,7 +255,7 @@ impl Color {
                 let h_prime = hue * 6.0;
                 let x = chroma * (1.0 - (h_prime % 2.0 - 1.0).abs());
 
-                let (r1, b1, g1) = {
+                let (r1, g1, b1) = {
                     if      h_prime <  1.0 { (chroma, x, 0.0) }
                     else if h_prime <  2.0 { (x, chroma, 0.0) }
                     else if h_prime <  3.0 { (0.0, chroma, x) }
@@ -136,14 +268,24 @@ impl Color {
                 Color::from_rgba(r1 + m, g1 + m, b1 + m, alpha)
             }
             ColorSpace::Lab | ColorSpace::LabA => {
-                todo!()
+                let [l, a, b, alpha] = self.components;
+
+                // components are stored scaled down by 100
+                let fy = (l * 100.0 + 16.0) / 116.0;
+                let fx = fy + (a * 100.0) / 500.0;
+                let fz = fy - (b * 100.0) / 200.0;
+                let [xn, yn, zn] = D65_WHITE;
+                let xyz = [lab_f_inv(fx) * xn, lab_f_inv(fy) * yn, lab_f_inv(fz) * zn];
+
+                let [r, g, b] = xyz_to_linear_rgb(xyz);
+                Color::from_rgba(linear_to_srgb(r), linear_to_srgb(g), linear_to_srgb(b), alpha)
             }
         }
     }
 
     pub fn to_hsv(&self) -> Color {
         match self.space {
-            ColorSpace::HSL | ColorSpace::HSLA => { todo!() }
+            ColorSpace::HSL | ColorSpace::HSLA => { self.to_rgb().to_hsv() }
             ColorSpace::HSV | ColorSpace::HSVA => { *self }
             ColorSpace::RGB | ColorSpace::RGBA => {
                 let [r, g, b, alpha] = self.components;
@@ -155,7 +297,7 @@ impl Color {
                 let value = max;
 
                 let hue = if chroma < f32::EPSILON { 0.0 } else {
-                    if      max == r { (g - b) / chroma       }
+                    if      max == r { ((g - b) / chroma).rem_euclid(6.0) }
                     else if max == g { (b - r) / chroma + 2.0 }
                     else if max == b { (r - g) / chroma + 4.0 }
                     else { unreachable!() }
@@ -165,7 +307,7 @@ impl Color {
 
                 Color::from_hsva(hue / 6.0, saturation, value, alpha)
             }
-            ColorSpace::Lab | ColorSpace::LabA => { todo!() }
+            ColorSpace::Lab | ColorSpace::LabA => { self.to_rgb().to_hsv() }
         }
     }
 
@@ -186,10 +328,10 @@ impl Color {
                 let min = slice_min(&[r, g, b]);
                 let chroma = max - min;
 
-                let lightness = (max - min) / 2.0;
+                let lightness = (max + min) / 2.0;
 
                 let hue = if chroma < f32::EPSILON { 0.0 } else {
-                    if      max == r { (g - b) / chroma       }
+                    if      max == r { ((g - b) / chroma).rem_euclid(6.0) }
                     else if max == g { (b - r) / chroma + 2.0 }
                     else if max == b { (r - g) / chroma + 4.0 }
                     else { unreachable!() }
@@ -200,20 +342,650 @@ impl Color {
 
                 Color::from_hsla(hue / 6.0, saturation, lightness, alpha)
             }
-            ColorSpace::Lab | ColorSpace::LabA => {
-                todo!()
-            }
+            ColorSpace::Lab | ColorSpace::LabA => { self.to_rgb().to_hsl() }
         }
     }
 
+    /// Converts to CIELAB with a D65 white point. Components are stored scaled down by 100, so L is
+    /// in [0, 1] and a/b are roughly in [-1, 1].
     pub fn to_lab(&self) -> Color {
-        todo!()
+        match self.space {
+            ColorSpace::Lab | ColorSpace::LabA => { *self }
+            _ => {
+                let [r, g, b, alpha] = self.to_rgb().components;
+                let [x, y, z] = linear_rgb_to_xyz([srgb_to_linear(r), srgb_to_linear(g), srgb_to_linear(b)]);
+
+                let [xn, yn, zn] = D65_WHITE;
+                let (fx, fy, fz) = (lab_f(x / xn), lab_f(y / yn), lab_f(z / zn));
+                let l = 116.0 * fy - 16.0;
+                let a = 500.0 * (fx - fy);
+                let b = 200.0 * (fy - fz);
+                Color::from_laba(l / 100.0, a / 100.0, b / 100.0, alpha)
+            }
+        }
+    }
+
+    /// Maps a color that's outside the sRGB gamut back into it by reducing its chroma in Lab space,
+    /// which keeps its hue and lightness (unlike clamping each RGB channel). Lightness outside
+    /// `[0, 1]` is clamped first. Colors already in gamut are returned unchanged. The result is in
+    /// the same ColorSpace as `self`.
+    pub fn clip_to_gamut(&self) -> Color {
+        let rgb = self.to_rgb();
+        let in_gamut = |c: &Color| c.components_3().iter().all(|&v| (-0.00001..=1.00001).contains(&v));
+        if in_gamut(&rgb) { return *self }
+
+        let [l, a, b, alpha] = self.to_lab().components;
+        let l = l.clamp(0.0, 1.0);
+        let with_chroma = |k: f32| Color::from_laba(l, a * k, b * k, alpha).to_rgb();
+        // binary search for the largest chroma scale that's still in gamut
+        let (mut low, mut high) = (0.0f32, 1.0f32);
+        for _ in 0..24 {
+            let mid = (low + high) * 0.5;
+            if in_gamut(&with_chroma(mid)) { low = mid } else { high = mid }
+        }
+        let mut clipped = with_chroma(low);
+        for c in clipped.components_3_mut() {
+            *c = c.clamp(0.0, 1.0);
+        }
+        clipped.convert(self.space);
+        Color { space: self.space, ..clipped }
+    }
+
+    /// Perceptual difference between two colors using the CIE76 formula (euclidean distance in Lab).
+    /// Returns the difference in standard ΔE units, where ~2.3 is a just-noticeable difference.
+    pub fn delta_e(&self, other: &Color) -> f32 {
+        let [l1, a1, b1] = *self.to_lab().components_3();
+        let [l2, a2, b2] = *other.to_lab().components_3();
+        ((l1 - l2).powi(2) + (a1 - a2).powi(2) + (b1 - b2).powi(2)).sqrt() * 100.0
+    }
+
+    /// Increases HSL lightness by `amount`, clamped to [0, 1]. Returns an HSL color.
+    pub fn lighten(&self, amount: f32) -> Color {
+        let mut c = self.to_hsl();
+        c.components[2] = (c.components[2] + amount).clamp(0.0, 1.0);
+        c
+    }
+
+    /// Decreases HSL lightness by `amount`, clamped to [0, 1]. Returns an HSL color.
+    pub fn darken(&self, amount: f32) -> Color { self.lighten(-amount) }
+
+    /// Increases HSL saturation by `amount`, clamped to [0, 1]. Returns an HSL color.
+    pub fn saturate(&self, amount: f32) -> Color {
+        let mut c = self.to_hsl();
+        c.components[1] = (c.components[1] + amount).clamp(0.0, 1.0);
+        c
+    }
+
+    /// Decreases HSL saturation by `amount`, clamped to [0, 1]. Returns an HSL color.
+    pub fn desaturate_hsl(&self, amount: f32) -> Color { self.saturate(-amount) }
+
+    /// Increases HSL saturation by `amount * (1 - saturation)`, so muted colors are boosted more
+    /// than already-vivid ones, then rescales to keep the original relative luminance. Negative
+    /// amounts reduce saturation. Returns an HSL color.
+    pub fn vibrance(&self, amount: f32) -> Color {
+        let luminance = self.sort_key_luminance();
+        let mut c = self.to_hsl();
+        let s = c.components[1];
+        c.components[1] = (s + amount * (1.0 - s)).clamp(0.0, 1.0);
+
+        let boosted_luminance = c.sort_key_luminance();
+        if boosted_luminance <= 0.0 { return c }
+        let scale = luminance / boosted_luminance;
+        let [r, g, b, alpha] = c.to_rgb().components;
+        let [r, g, b] = [r, g, b].map(|x| linear_to_srgb((srgb_to_linear(x) * scale).clamp(0.0, 1.0)));
+        Color { space: c.space, ..Color::from_rgba(r, g, b, alpha).to_hsl() }
+    }
+
+    /// Rotates the hue by the given number of degrees, wrapping around. Returns an HSL color.
+    pub fn rotate_hue(&self, degrees: f32) -> Color {
+        let mut c = self.to_hsl();
+        c.components[0] = (c.components[0] + degrees / 360.0).rem_euclid(1.0);
+        c
+    }
+
+    /// Returns the hue in degrees, in the range [0, 360). Colors not already in HSL or HSV are
+    /// converted to HSL first.
+    pub fn hue_degrees(&self) -> f32 {
+        self.hue_color().components[0] * 360.0
+    }
+
+    /// Returns a copy of this color with the hue set to the given number of degrees, wrapped into
+    /// [0, 360). HSL and HSV colors keep their space, other colors are converted to HSL.
+    pub fn with_hue_degrees(&self, degrees: f32) -> Color {
+        let mut c = self.hue_color();
+        c.components[0] = degrees.rem_euclid(360.0) / 360.0;
+        c
+    }
+
+    // returns self if it has a hue component, otherwise converts to HSL
+    fn hue_color(&self) -> Color {
+        match self.space {
+            ColorSpace::HSL | ColorSpace::HSLA | ColorSpace::HSV | ColorSpace::HSVA => *self,
+            _ => self.to_hsl()
+        }
+    }
+
+    /// Returns the circular distance between the hues of two colors, in the range [0, 0.5].
+    pub fn hue_distance(&self, other: &Color) -> f32 {
+        let difference = (self.to_hsl().components[0] - other.to_hsl().components[0]).rem_euclid(1.0);
+        difference.min(1.0 - difference)
+    }
+
+    /// Looks up a CSS named color, e.g. `"red"` or `"cornflowerblue"`, returning it as an RGB color.
+    /// Names are case-insensitive. Returns `None` for unknown names.
+    pub fn named(name: &str) -> Option<Color> {
+        let name = name.to_ascii_lowercase();
+        let idx = CSS_NAMED_COLORS.binary_search_by(|&(n, _)| n.cmp(name.as_str())).ok()?;
+        let [r, g, b] = hex_to_rgb(CSS_NAMED_COLORS[idx].1);
+        Some(Color::from_rgb(r, g, b))
+    }
+
+    /// Like `named`, but converts the color to the given ColorSpace.
+    pub fn named_in(name: &str, space: ColorSpace) -> Option<Color> {
+        let mut color = Color::named(name)?;
+        color.convert(space);
+        Some(Color { space, ..color })
+    }
+
+    /// Returns the name of the CSS named color closest to this color in RGB space.
+    /// Where multiple names share the same value (e.g. "aqua" and "cyan"), the first
+    /// alphabetically is returned.
+    pub fn nearest_named(&self) -> &'static str {
+        let [r, g, b] = *self.to_rgb().components_3();
+        let mut nearest = CSS_NAMED_COLORS[0].0;
+        let mut nearest_dist = f32::INFINITY;
+        for &(name, hex) in CSS_NAMED_COLORS {
+            let [nr, ng, nb] = hex_to_rgb(hex);
+            let dist = (r - nr).powi(2) + (g - ng).powi(2) + (b - nb).powi(2);
+            if dist < nearest_dist {
+                nearest = name;
+                nearest_dist = dist;
+            }
+        }
+        nearest
+    }
+
+    /// Multiplies the RGB components by a 3x3 matrix, e.g. for white balance or channel mixing.
+    /// Converts to RGB first and returns an RGB color. Alpha is left unchanged.
+    pub fn apply_matrix(&self, m: Matrix3<f32>) -> Color {
+        let mut c = self.to_rgb();
+        let [r, g, b] = *c.components_3();
+        let result = m * Vector3::new(r, g, b);
+        *c.components_3_mut() = result.into();
+        c
+    }
+
+    /// Multiplies the RGB components by a 4x4 affine matrix, treating the color as the point
+    /// `(r, g, b, 1)` so the last column acts as an offset. Converts to RGB first and returns an
+    /// RGB color. Alpha is left unchanged.
+    pub fn apply_affine(&self, m: Matrix4<f32>) -> Color {
+        let mut c = self.to_rgb();
+        let [r, g, b] = *c.components_3();
+        let result = m * Vector4::new(r, g, b, 1.0);
+        *c.components_3_mut() = result.truncate().into();
+        c
+    }
+
+    /// Returns the relative luminance of the color (from linear RGB), for sorting palettes by
+    /// brightness. Since `f32` isn't `Ord`, use it with `sort_by` and `f32::total_cmp`:
+    /// ```
+    /// # use toolbelt::Color;
+    /// # let mut palette = vec![Color::from_rgb(1.0, 1.0, 1.0), Color::from_rgb(0.0, 0.0, 0.0)];
+    /// palette.sort_by(|a, b| a.sort_key_luminance().total_cmp(&b.sort_key_luminance()));
+    /// ```
+    pub fn sort_key_luminance(&self) -> f32 {
+        let [r, g, b] = *self.to_rgb().components_3();
+        0.2126 * srgb_to_linear(r) + 0.7152 * srgb_to_linear(g) + 0.0722 * srgb_to_linear(b)
+    }
+
+    /// Returns a sort key of the quantized HSL components: hue in whole degrees [0, 360), and
+    /// saturation and lightness in whole percent [0, 100]. Can be used directly with `sort_by_key`.
+    pub fn sort_key_hsl(&self) -> (u32, u32, u32) {
+        let [h, s, l] = *self.to_hsl().components_3();
+        ((h * 360.0).round() as u32 % 360, (s * 100.0).round() as u32, (l * 100.0).round() as u32)
+    }
+
+    /// Averages a set of colors in the given ColorSpace. Hue is averaged circularly for HSL/HSV.
+    /// Returns `None` if `colors` is empty.
+    pub fn mix_many(colors: &[Color], space: ColorSpace) -> Option<Color> {
+        if colors.is_empty() { return None }
+
+        let circular_hue = matches!(space, ColorSpace::HSL | ColorSpace::HSLA | ColorSpace::HSV | ColorSpace::HSVA);
+        let mut sums = [0.0f32; 4];
+        let (mut hue_x, mut hue_y) = (0.0f32, 0.0f32);
+        for color in colors {
+            let mut color = *color;
+            color.convert(space);
+            for (sum, component) in sums.iter_mut().zip(color.components) {
+                *sum += component;
+            }
+            if circular_hue {
+                let angle = color.components[0] * std::f32::consts::TAU;
+                hue_x += angle.cos();
+                hue_y += angle.sin();
+            }
+        }
+
+        let count = colors.len() as f32;
+        let mut components = sums.map(|sum| sum / count);
+        if circular_hue {
+            components[0] = if hue_x.abs() < f32::EPSILON && hue_y.abs() < f32::EPSILON { 0.0 }
+                            else { hue_y.atan2(hue_x).rem_euclid(std::f32::consts::TAU) / std::f32::consts::TAU };
+        }
+        Some(Color { components, space })
+    }
+
+    /// Reduces `colors` to a palette of at most `palette_size` colors using median-cut quantization
+    /// in RGB space: the set is repeatedly split at the median of whichever group has the widest
+    /// channel range, and each final group is averaged. If there are no more distinct colors than
+    /// `palette_size`, the distinct colors are returned as-is. All returned colors are RGBA.
+    pub fn median_cut(colors: &[Color], palette_size: usize) -> Vec<Color> {
+        if colors.is_empty() || palette_size == 0 { return Vec::new() }
+        let colors: Vec<Color> = colors.iter().map(|c| Color { space: ColorSpace::RGBA, ..c.to_rgb() }).collect();
+
+        let mut distinct: Vec<Color> = Vec::new();
+        for c in &colors {
+            if !distinct.iter().any(|d| d.components == c.components) {
+                distinct.push(*c);
+                if distinct.len() > palette_size { break }
+            }
+        }
+        if distinct.len() <= palette_size { return distinct }
+
+        // returns the channel with the widest range in the group, and that range
+        let widest_channel = |group: &[Color]| -> (usize, f32) {
+            (0..3).map(|ch| {
+                let values: Vec<f32> = group.iter().map(|c| c.components[ch]).collect();
+                (ch, slice_max(&values) - slice_min(&values))
+            }).fold((0, -1.0), |best, next| if next.1 > best.1 { next } else { best })
+        };
+
+        let mut groups = vec![colors];
+        while groups.len() < palette_size {
+            let Some((idx, channel)) = groups.iter().enumerate()
+                .map(|(i, group)| (i, widest_channel(group)))
+                .filter(|(_, (_, range))| *range > 0.0)
+                .max_by(|a, b| a.1.1.total_cmp(&b.1.1))
+                .map(|(i, (channel, _))| (i, channel))
+            else { break };
+
+            let mut group = groups.swap_remove(idx);
+            group.sort_by(|a, b| a.components[channel].total_cmp(&b.components[channel]));
+            let upper = group.split_off(group.len() / 2);
+            groups.push(group);
+            groups.push(upper);
+        }
+        groups.iter().filter_map(|group| Color::mix_many(group, ColorSpace::RGBA)).collect()
+    }
+
+    /// Composites this color over `background` using the standard straight-alpha "source over"
+    /// operator. Both colors are converted to RGB first, and the result is RGBA with alpha
+    /// `src_a + dst_a * (1 - src_a)`. Compositing two fully transparent colors gives transparent black.
+    pub fn over(&self, background: &Color) -> Color {
+        let src = self.to_rgb();
+        let dst = background.to_rgb();
+        let (src_a, dst_a) = (src.alpha(), dst.alpha());
+        let out_a = src_a + dst_a * (1.0 - src_a);
+        if out_a <= 0.0 { return Color::from_rgba(0.0, 0.0, 0.0, 0.0) }
+
+        let blend = |i: usize| (src.components[i] * src_a + dst.components[i] * dst_a * (1.0 - src_a)) / out_a;
+        Color::from_rgba(blend(0), blend(1), blend(2), out_a)
+    }
+
+    /// Interpolates between this color and `other` by `t`. The sRGB channels are linearized before
+    /// blending and re-encoded afterwards, which avoids the dark, muddy midpoints of blending the
+    /// gamma-encoded values directly. Alpha is interpolated linearly. Returns an RGBA color.
+    pub fn mix(&self, other: &Color, t: f32) -> Color {
+        let a = self.to_rgb().components;
+        let b = other.to_rgb().components;
+        let blend = |i: usize| linear_to_srgb(lerp(srgb_to_linear(a[i]), srgb_to_linear(b[i]), t));
+        Color::from_rgba(blend(0), blend(1), blend(2), lerp(a[3], b[3], t))
+    }
+
+    /// Runs each RGB channel through `curve`, e.g. for tone or gamma adjustments. Channel values
+    /// are clamped to [0, 1] before and after evaluating the curve, and alpha is left untouched.
+    /// The result is in the same ColorSpace as `self`.
+    pub fn apply_curve(&self, curve: &FloatCurve) -> Color {
+        self.apply_channel_curves(curve, curve, curve)
+    }
+
+    /// Like `apply_curve`, but with a separate curve for each of the red, green, and blue channels.
+    pub fn apply_channel_curves(&self, r: &FloatCurve, g: &FloatCurve, b: &FloatCurve) -> Color {
+        let rgb = self.to_rgb();
+        let [cr, cg, cb, alpha] = rgb.components;
+        let eval = |curve: &FloatCurve, c: f32| curve.get_value(c.clamp(0.0, 1.0)).clamp(0.0, 1.0);
+        let mut result = Color { components: [eval(r, cr), eval(g, cg), eval(b, cb), alpha], space: rgb.space };
+        result.convert(self.space);
+        Color { space: self.space, ..result }
+    }
+
+    /// Simulates how this color appears to someone with the given color vision deficiency, for
+    /// accessibility previews. The linear RGB color is converted to LMS cone space, projected onto
+    /// the plane of colors the dichromat can distinguish (Viénot et al. 1999), and converted back.
+    /// Alpha is left untouched, and the result is in the same ColorSpace as `self`.
+    pub fn simulate_cvd(&self, kind: Cvd) -> Color {
+        let projection = match kind {
+            Cvd::Normal => return *self,
+            Cvd::Protanopia => &CVD_PROTANOPIA,
+            Cvd::Deuteranopia => &CVD_DEUTERANOPIA,
+            Cvd::Tritanopia => &CVD_TRITANOPIA,
+        };
+        let [r, g, b, alpha] = self.to_rgb().components;
+        let lms = mat3_mul(&RGB_TO_LMS, [srgb_to_linear(r), srgb_to_linear(g), srgb_to_linear(b)]);
+        let [r, g, b] = mat3_mul(&LMS_TO_RGB, mat3_mul(projection, lms)).map(|c| linear_to_srgb(c.clamp(0.0, 1.0)));
+        let mut simulated = Color::from_rgba(r, g, b, alpha);
+        simulated.convert(self.space);
+        Color { space: self.space, ..simulated }
+    }
+
+    /// Performs a chromatic adaptation of this color from the `from` illuminant to the `to`
+    /// illuminant using the Bradford transform, e.g. to convert colors measured under D50 for use
+    /// under D65. The result is in the same ColorSpace as `self`.
+    pub fn adapt_white_point(&self, from: WhitePoint, to: WhitePoint) -> Color {
+        if from == to { return *self }
+        let [r, g, b, alpha] = self.to_rgb().components;
+        let xyz = linear_rgb_to_xyz([srgb_to_linear(r), srgb_to_linear(g), srgb_to_linear(b)]);
+
+        let cone_from = mat3_mul(&BRADFORD, from.xyz());
+        let cone_to = mat3_mul(&BRADFORD, to.xyz());
+        let cone = mat3_mul(&BRADFORD, xyz);
+        let adapted_cone = [0, 1, 2].map(|i| cone[i] * cone_to[i] / cone_from[i]);
+
+        let [r, g, b] = xyz_to_linear_rgb(mat3_mul(&BRADFORD_INV, adapted_cone));
+        let mut adapted = Color::from_rgba(linear_to_srgb(r), linear_to_srgb(g), linear_to_srgb(b), alpha);
+        adapted.convert(self.space);
+        Color { space: self.space, ..adapted }
     }
 
     // TODO: space conversions
     // TODO: linear <-> srgb conversions
 }
 
+/// A kind of color vision deficiency, for `Color::simulate_cvd`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cvd {
+    /// Normal color vision. Simulating it returns the color unchanged.
+    Normal,
+    /// No functioning long-wavelength (red) cones.
+    Protanopia,
+    /// No functioning medium-wavelength (green) cones.
+    Deuteranopia,
+    /// No functioning short-wavelength (blue) cones.
+    Tritanopia,
+}
+
+/// A standard illuminant, used as the reference white for chromatic adaptation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WhitePoint {
+    /// Horizon light, used for printing and the ICC profile connection space.
+    D50,
+    /// Mid-morning daylight.
+    D55,
+    /// Noon daylight. The white point of sRGB, and the one this module assumes everywhere else.
+    D65,
+    /// North sky daylight.
+    D75,
+    /// Incandescent tungsten light.
+    A,
+    /// Equal-energy illuminant.
+    E,
+}
+
+impl WhitePoint {
+    /// Returns the CIE XYZ coordinates of the illuminant (CIE 1931 2° observer), normalized so Y = 1.
+    pub const fn xyz(self) -> [f32; 3] {
+        match self {
+            WhitePoint::D50 => [0.96422, 1.0, 0.82521],
+            WhitePoint::D55 => [0.95682, 1.0, 0.92149],
+            WhitePoint::D65 => D65_WHITE,
+            WhitePoint::D75 => [0.94972, 1.0, 1.22638],
+            WhitePoint::A => [1.0985, 1.0, 0.35585],
+            WhitePoint::E => [1.0, 1.0, 1.0],
+        }
+    }
+}
+
+/// CIE XYZ coordinates of the D65 standard illuminant.
+const D65_WHITE: [f32; 3] = [0.95047, 1.0, 1.08883];
+
+/// Bradford cone response matrix and its inverse, for chromatic adaptation. Row-major.
+const BRADFORD: [[f32; 3]; 3] = [
+    [ 0.8951,  0.2664, -0.1614],
+    [-0.7502,  1.7135,  0.0367],
+    [ 0.0389, -0.0685,  1.0296],
+];
+const BRADFORD_INV: [[f32; 3]; 3] = [
+    [ 0.9869929, -0.1470543, 0.1599627],
+    [ 0.4323053,  0.5183603, 0.0492912],
+    [-0.0085287,  0.0400428, 0.9684867],
+];
+
+/// Linear sRGB to LMS cone response and back, as used for color vision deficiency simulation.
+const RGB_TO_LMS: [[f32; 3]; 3] = [
+    [17.8824,   43.5161,  4.11935],
+    [ 3.45565,  27.1554,  3.86714],
+    [ 0.0299566, 0.184309, 1.46709],
+];
+const LMS_TO_RGB: [[f32; 3]; 3] = [
+    [ 0.08094445, -0.13050441,   0.11672107],
+    [-0.010248533,  0.05401933, -0.11361471],
+    [-0.00036529694, -0.0041216147, 0.6935114],
+];
+// each replaces the missing cone's response with a combination of the other two, chosen so white
+// and one anchor color (blue for protan/deutan, red for tritan) are unchanged
+const CVD_PROTANOPIA: [[f32; 3]; 3] = [
+    [0.0, 2.02344, -2.52581],
+    [0.0, 1.0, 0.0],
+    [0.0, 0.0, 1.0],
+];
+const CVD_DEUTERANOPIA: [[f32; 3]; 3] = [
+    [1.0, 0.0, 0.0],
+    [0.494207, 0.0, 1.24827],
+    [0.0, 0.0, 1.0],
+];
+const CVD_TRITANOPIA: [[f32; 3]; 3] = [
+    [1.0, 0.0, 0.0],
+    [0.0, 1.0, 0.0],
+    [-0.012245, 0.0720345, 0.0],
+];
+
+fn mat3_mul(m: &[[f32; 3]; 3], v: [f32; 3]) -> [f32; 3] {
+    m.map(|row| row[0] * v[0] + row[1] * v[1] + row[2] * v[2])
+}
+
+// linear sRGB <-> CIE XYZ, both relative to D65
+fn linear_rgb_to_xyz(rgb: [f32; 3]) -> [f32; 3] {
+    const M: [[f32; 3]; 3] = [
+        [0.4124564, 0.3575761, 0.1804375],
+        [0.2126729, 0.7151522, 0.072175],
+        [0.0193339, 0.119192, 0.9503041],
+    ];
+    mat3_mul(&M, rgb)
+}
+
+fn xyz_to_linear_rgb(xyz: [f32; 3]) -> [f32; 3] {
+    const M: [[f32; 3]; 3] = [
+        [ 3.2404542, -1.5371385, -0.4985314],
+        [-0.969266,   1.8760108,  0.041556],
+        [ 0.0556434, -0.2040259,  1.0572252],
+    ];
+    mat3_mul(&M, xyz)
+}
+
+fn lab_f(t: f32) -> f32 {
+    const DELTA: f32 = 6.0 / 29.0;
+    if t > DELTA * DELTA * DELTA { t.cbrt() } else { t / (3.0 * DELTA * DELTA) + 4.0 / 29.0 }
+}
+
+fn lab_f_inv(t: f32) -> f32 {
+    const DELTA: f32 = 6.0 / 29.0;
+    if t > DELTA { t * t * t } else { 3.0 * DELTA * DELTA * (t - 4.0 / 29.0) }
+}
+
+fn srgb_to_linear(c: f32) -> f32 {
+    if c <= 0.04045 { c / 12.92 } else { ((c + 0.055) / 1.055).powf(2.4) }
+}
+
+fn linear_to_srgb(c: f32) -> f32 {
+    if c <= 0.0031308 { c * 12.92 } else { 1.055 * c.powf(1.0 / 2.4) - 0.055 }
+}
+
+/// CSS named colors as (name, 0xRRGGBB), sorted by name.
+const CSS_NAMED_COLORS: &[(&str, u32)] = &[
+    ("aliceblue", 0xf0f8ff),
+    ("antiquewhite", 0xfaebd7),
+    ("aqua", 0x00ffff),
+    ("aquamarine", 0x7fffd4),
+    ("azure", 0xf0ffff),
+    ("beige", 0xf5f5dc),
+    ("bisque", 0xffe4c4),
+    ("black", 0x000000),
+    ("blanchedalmond", 0xffebcd),
+    ("blue", 0x0000ff),
+    ("blueviolet", 0x8a2be2),
+    ("brown", 0xa52a2a),
+    ("burlywood", 0xdeb887),
+    ("cadetblue", 0x5f9ea0),
+    ("chartreuse", 0x7fff00),
+    ("chocolate", 0xd2691e),
+    ("coral", 0xff7f50),
+    ("cornflowerblue", 0x6495ed),
+    ("cornsilk", 0xfff8dc),
+    ("crimson", 0xdc143c),
+    ("cyan", 0x00ffff),
+    ("darkblue", 0x00008b),
+    ("darkcyan", 0x008b8b),
+    ("darkgoldenrod", 0xb8860b),
+    ("darkgray", 0xa9a9a9),
+    ("darkgreen", 0x006400),
+    ("darkgrey", 0xa9a9a9),
+    ("darkkhaki", 0xbdb76b),
+    ("darkmagenta", 0x8b008b),
+    ("darkolivegreen", 0x556b2f),
+    ("darkorange", 0xff8c00),
+    ("darkorchid", 0x9932cc),
+    ("darkred", 0x8b0000),
+    ("darksalmon", 0xe9967a),
+    ("darkseagreen", 0x8fbc8f),
+    ("darkslateblue", 0x483d8b),
+    ("darkslategray", 0x2f4f4f),
+    ("darkslategrey", 0x2f4f4f),
+    ("darkturquoise", 0x00ced1),
+    ("darkviolet", 0x9400d3),
+    ("deeppink", 0xff1493),
+    ("deepskyblue", 0x00bfff),
+    ("dimgray", 0x696969),
+    ("dimgrey", 0x696969),
+    ("dodgerblue", 0x1e90ff),
+    ("firebrick", 0xb22222),
+    ("floralwhite", 0xfffaf0),
+    ("forestgreen", 0x228b22),
+    ("fuchsia", 0xff00ff),
+    ("gainsboro", 0xdcdcdc),
+    ("ghostwhite", 0xf8f8ff),
+    ("gold", 0xffd700),
+    ("goldenrod", 0xdaa520),
+    ("gray", 0x808080),
+    ("green", 0x008000),
+    ("greenyellow", 0xadff2f),
+    ("grey", 0x808080),
+    ("honeydew", 0xf0fff0),
+    ("hotpink", 0xff69b4),
+    ("indianred", 0xcd5c5c),
+    ("indigo", 0x4b0082),
+    ("ivory", 0xfffff0),
+    ("khaki", 0xf0e68c),
+    ("lavender", 0xe6e6fa),
+    ("lavenderblush", 0xfff0f5),
+    ("lawngreen", 0x7cfc00),
+    ("lemonchiffon", 0xfffacd),
+    ("lightblue", 0xadd8e6),
+    ("lightcoral", 0xf08080),
+    ("lightcyan", 0xe0ffff),
+    ("lightgoldenrodyellow", 0xfafad2),
+    ("lightgray", 0xd3d3d3),
+    ("lightgreen", 0x90ee90),
+    ("lightgrey", 0xd3d3d3),
+    ("lightpink", 0xffb6c1),
+    ("lightsalmon", 0xffa07a),
+    ("lightseagreen", 0x20b2aa),
+    ("lightskyblue", 0x87cefa),
+    ("lightslategray", 0x778899),
+    ("lightslategrey", 0x778899),
+    ("lightsteelblue", 0xb0c4de),
+    ("lightyellow", 0xffffe0),
+    ("lime", 0x00ff00),
+    ("limegreen", 0x32cd32),
+    ("linen", 0xfaf0e6),
+    ("magenta", 0xff00ff),
+    ("maroon", 0x800000),
+    ("mediumaquamarine", 0x66cdaa),
+    ("mediumblue", 0x0000cd),
+    ("mediumorchid", 0xba55d3),
+    ("mediumpurple", 0x9370db),
+    ("mediumseagreen", 0x3cb371),
+    ("mediumslateblue", 0x7b68ee),
+    ("mediumspringgreen", 0x00fa9a),
+    ("mediumturquoise", 0x48d1cc),
+    ("mediumvioletred", 0xc71585),
+    ("midnightblue", 0x191970),
+    ("mintcream", 0xf5fffa),
+    ("mistyrose", 0xffe4e1),
+    ("moccasin", 0xffe4b5),
+    ("navajowhite", 0xffdead),
+    ("navy", 0x000080),
+    ("oldlace", 0xfdf5e6),
+    ("olive", 0x808000),
+    ("olivedrab", 0x6b8e23),
+    ("orange", 0xffa500),
+    ("orangered", 0xff4500),
+    ("orchid", 0xda70d6),
+    ("palegoldenrod", 0xeee8aa),
+    ("palegreen", 0x98fb98),
+    ("paleturquoise", 0xafeeee),
+    ("palevioletred", 0xdb7093),
+    ("papayawhip", 0xffefd5),
+    ("peachpuff", 0xffdab9),
+    ("peru", 0xcd853f),
+    ("pink", 0xffc0cb),
+    ("plum", 0xdda0dd),
+    ("powderblue", 0xb0e0e6),
+    ("purple", 0x800080),
+    ("rebeccapurple", 0x663399),
+    ("red", 0xff0000),
+    ("rosybrown", 0xbc8f8f),
+    ("royalblue", 0x4169e1),
+    ("saddlebrown", 0x8b4513),
+    ("salmon", 0xfa8072),
+    ("sandybrown", 0xf4a460),
+    ("seagreen", 0x2e8b57),
+    ("seashell", 0xfff5ee),
+    ("sienna", 0xa0522d),
+    ("silver", 0xc0c0c0),
+    ("skyblue", 0x87ceeb),
+    ("slateblue", 0x6a5acd),
+    ("slategray", 0x708090),
+    ("slategrey", 0x708090),
+    ("snow", 0xfffafa),
+    ("springgreen", 0x00ff7f),
+    ("steelblue", 0x4682b4),
+    ("tan", 0xd2b48c),
+    ("teal", 0x008080),
+    ("thistle", 0xd8bfd8),
+    ("tomato", 0xff6347),
+    ("turquoise", 0x40e0d0),
+    ("violet", 0xee82ee),
+    ("wheat", 0xf5deb3),
+    ("white", 0xffffff),
+    ("whitesmoke", 0xf5f5f5),
+    ("yellow", 0xffff00),
+    ("yellowgreen", 0x9acd32),
+];
+
+fn hex_to_rgb(hex: u32) -> [f32; 3] {
+    [((hex >> 16) & 0xff) as f32 / 255.0, ((hex >> 8) & 0xff) as f32 / 255.0, (hex & 0xff) as f32 / 255.0]
+}
+
 impl Index<usize> for Color {
     type Output = f32;
 
@@ -261,3 +1033,482 @@ impl IndexMut<Range<usize>> for Color {
         }
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    macro_rules! assert_eq_float {
+        ($a:expr, $b:expr) => { assert!((($a) - ($b)).abs() < 0.0001, "{} != {}", $a, $b) }
+    }
+
+    #[test]
+    fn test_color_space_alpha_variants() {
+        let pairs = [
+            (ColorSpace::RGB, ColorSpace::RGBA),
+            (ColorSpace::HSL, ColorSpace::HSLA),
+            (ColorSpace::HSV, ColorSpace::HSVA),
+            (ColorSpace::Lab, ColorSpace::LabA),
+        ];
+        for (base, alpha) in pairs {
+            assert_eq!(base.base(), base);
+            assert_eq!(alpha.base(), base);
+            assert_eq!(base.with_alpha(), alpha);
+            assert_eq!(alpha.with_alpha(), alpha);
+        }
+        assert_eq!(Color::from_rgb(0.1, 0.2, 0.3).with_alpha(0.5).space, ColorSpace::RGBA);
+    }
+
+    #[test]
+    fn test_with_alpha_updates_space() {
+        assert_eq!(Color::from_hsv(0.2, 0.5, 0.5).check_alpha(), None);
+        assert_eq!(Color::from_hsv(0.2, 0.5, 0.5).with_alpha(0.3).check_alpha(), Some(0.3));
+        assert_eq!(Color::from_hsla(0.2, 0.5, 0.5, 0.7).with_alpha(0.3).space, ColorSpace::HSLA);
+        assert_eq!(Color::from_lab(0.5, 0.0, 0.0).opaque().check_alpha(), Some(1.0));
+        assert_eq!(Color::from_rgb(1.0, 0.0, 0.0).transparent().check_alpha(), Some(0.0));
+    }
+
+    #[test]
+    fn test_lighten_darken() {
+        let c = Color::from_hsl(0.5, 0.5, 0.4);
+        assert_eq_float!(c.lighten(0.1).components[2], 0.5);
+        assert_eq_float!(c.darken(0.1).components[2], 0.3);
+        assert_eq_float!(c.lighten(1.0).components[2], 1.0);
+        assert_eq_float!(c.saturate(0.2).components[1], 0.7);
+        assert_eq_float!(c.desaturate_hsl(0.7).components[1], 0.0);
+    }
+
+    #[test]
+    fn test_vibrance() {
+        // near-gray colors get most of the boost
+        let muted = Color::from_rgb(0.5, 0.45, 0.45);
+        let s = muted.to_hsl().components[1];
+        let vibrant = muted.vibrance(0.5);
+        assert_eq!(vibrant.space.base(), ColorSpace::HSL);
+        assert!(vibrant.components[1] - s > 0.4);
+        assert_eq_float!(vibrant.sort_key_luminance(), muted.sort_key_luminance());
+
+        // vivid colors barely change, unlike with saturate
+        let vivid = Color::from_rgb(0.9, 0.1, 0.1);
+        let s = vivid.to_hsl().components[1];
+        let vibrant = vivid.vibrance(0.5);
+        let saturated = vivid.saturate(0.5);
+        assert!(vibrant.components[1] - s < 0.11);
+        assert!(saturated.components[1] - s > vibrant.components[1] - s);
+        assert_eq_float!(vibrant.sort_key_luminance(), vivid.sort_key_luminance());
+
+        // fully saturated colors are unchanged
+        let red = Color::from_rgb(1.0, 0.0, 0.0);
+        let vibrant = red.vibrance(1.0).to_rgb();
+        for i in 0..3 {
+            assert_eq_float!(vibrant.components[i], red.components[i]);
+        }
+
+        let black = Color::from_hsla(0.0, 0.0, 0.0, 0.5).vibrance(0.5);
+        assert_eq_float!(black.components[2], 0.0);
+        assert_eq_float!(black.alpha(), 0.5);
+    }
+
+    #[test]
+    fn test_rotate_hue() {
+        let c = Color::from_hsl(0.25, 0.5, 0.4);
+        assert_eq_float!(c.rotate_hue(360.0).components[0], 0.25);
+        assert_eq_float!(c.rotate_hue(180.0).components[0], 0.75);
+        assert_eq_float!(c.rotate_hue(-180.0).components[0], 0.75);
+    }
+
+    #[test]
+    fn test_hue_distance() {
+        let red = Color::from_rgb(1.0, 0.0, 0.0);
+        let cyan = Color::from_rgb(0.0, 1.0, 1.0);
+        assert_eq_float!(red.hue_distance(&cyan), 0.5);
+        assert_eq_float!(red.hue_distance(&red), 0.0);
+        let a = Color::from_hsv(0.95, 1.0, 1.0);
+        let b = Color::from_hsv(0.05, 1.0, 1.0);
+        assert_eq_float!(a.hue_distance(&b), 0.1);
+    }
+
+    #[test]
+    fn test_nearest_named() {
+        assert_eq!(Color::from_rgb(1.0, 0.0, 0.0).nearest_named(), "red");
+        assert_eq!(Color::from_rgb(0.98, 0.02, 0.01).nearest_named(), "red");
+        assert_eq!(Color::from_rgb(0.0, 0.0, 0.0).nearest_named(), "black");
+        assert_eq!(Color::from_hsv(0.0, 0.0, 1.0).nearest_named(), "white");
+    }
+
+    #[test]
+    fn test_named() {
+        assert_eq!(Color::named("red"), Some(Color::from_rgb(1.0, 0.0, 0.0)));
+        assert_eq!(Color::named("Cyan"), Some(Color::from_rgb(0.0, 1.0, 1.0)));
+        assert_eq!(Color::named("cyan"), Color::named("aqua"));
+        assert_eq!(Color::named("rebeccapurple").unwrap().as_bytes(), [0x66, 0x33, 0x99, 0xff]);
+        assert_eq!(Color::named("notacolor"), None);
+
+        let green = Color::named_in("lime", ColorSpace::HSV).unwrap();
+        assert_eq!(green.space, ColorSpace::HSV);
+        assert_eq_float!(green.hue_degrees(), 120.0);
+        assert_eq!(Color::named_in("white", ColorSpace::LabA).unwrap().space, ColorSpace::LabA);
+        // the lookup is a binary search, so every entry must be found
+        for &(name, hex) in CSS_NAMED_COLORS {
+            assert_eq!(Color::named(name).map(|c| *c.components_3()), Some(hex_to_rgb(hex)), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_channels() {
+        let c = Color::from_hsva(0.0, 1.0, 1.0, 0.5);
+        assert_eq_float!(c.r(), 1.0);
+        assert_eq_float!(c.g(), 0.0);
+        assert_eq_float!(c.b(), 0.0);
+        assert_eq_float!(c.a(), 0.5);
+    }
+
+    #[test]
+    fn test_swizzle() {
+        let c = Color::from_rgba(0.1, 0.2, 0.3, 0.4);
+        assert_eq!(c.swizzle([2, 1, 0, 3]), [0.3, 0.2, 0.1, 0.4]);
+        assert_eq!(c.swizzle([3, 3, 0, 0]), [0.4, 0.4, 0.1, 0.1]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_swizzle_out_of_bounds_should_panic() {
+        Color::from_rgb(0.1, 0.2, 0.3).swizzle([0, 1, 2, 4]);
+    }
+
+    #[test]
+    fn test_hue_degrees() {
+        let c = Color::from_hsv(0.0, 1.0, 1.0).with_hue_degrees(180.0);
+        assert_eq!(c.space, ColorSpace::HSV);
+        assert_eq_float!(c.hue_degrees(), 180.0);
+        assert_eq_float!(c.with_hue_degrees(370.0).hue_degrees(), 10.0);
+        assert_eq_float!(c.with_hue_degrees(-90.0).hue_degrees(), 270.0);
+        assert_eq_float!(Color::from_rgb(0.0, 1.0, 1.0).hue_degrees(), 180.0);
+    }
+
+    #[test]
+    fn test_apply_matrix() {
+        let c = Color::from_rgba(0.2, 0.4, 0.6, 0.5);
+        assert_eq!(c.apply_matrix(Matrix3::from_scale(1.0)), c);
+        // swap red and blue
+        let swap = Matrix3::new(0.0, 0.0, 1.0, 0.0, 1.0, 0.0, 1.0, 0.0, 0.0);
+        assert_eq!(c.apply_matrix(swap), Color::from_rgba(0.6, 0.4, 0.2, 0.5));
+    }
+
+    #[test]
+    fn test_apply_affine() {
+        let c = Color::from_rgb(0.2, 0.4, 0.6);
+        assert_eq!(c.apply_affine(Matrix4::from_scale(1.0)), c);
+        let offset = Matrix4::from_translation(Vector3::new(0.1, 0.0, -0.1));
+        let result = c.apply_affine(offset);
+        assert_eq_float!(result.components[0], 0.3);
+        assert_eq_float!(result.components[2], 0.5);
+    }
+
+    #[test]
+    fn test_sort_key_luminance() {
+        let black = Color::from_rgb(0.0, 0.0, 0.0);
+        let blue = Color::from_rgb(0.0, 0.0, 1.0);
+        let red = Color::from_rgb(1.0, 0.0, 0.0);
+        let green = Color::from_rgb(0.0, 1.0, 0.0);
+        let white = Color::from_hsv(0.0, 0.0, 1.0);
+        let mut palette = vec![white, green, black, red, blue];
+        palette.sort_by(|a, b| a.sort_key_luminance().total_cmp(&b.sort_key_luminance()));
+        assert_eq!(palette, vec![black, blue, red, green, white]);
+    }
+
+    #[test]
+    fn test_sort_key_hsl() {
+        assert_eq!(Color::from_hsl(0.5, 0.25, 0.755).sort_key_hsl(), (180, 25, 76));
+        let mut palette = vec![Color::from_hsl(0.5, 1.0, 0.5), Color::from_hsl(0.1, 1.0, 0.5), Color::from_hsl(0.1, 0.5, 0.5)];
+        palette.sort_by_key(|c| c.sort_key_hsl());
+        assert_eq!(palette, vec![Color::from_hsl(0.1, 0.5, 0.5), Color::from_hsl(0.1, 1.0, 0.5), Color::from_hsl(0.5, 1.0, 0.5)]);
+    }
+
+    #[test]
+    fn test_lab_conversion() {
+        let white = Color::from_rgb(1.0, 1.0, 1.0).to_lab();
+        assert_eq_float!(white.components[0], 1.0);
+        assert!(white.components[1].abs() < 0.001 && white.components[2].abs() < 0.001);
+
+        let c = Color::from_rgb(0.8, 0.3, 0.1);
+        let back = c.to_lab().to_rgb();
+        for i in 0..3 {
+            assert_eq_float!(back.components[i], c.components[i]);
+        }
+    }
+
+    #[test]
+    fn test_delta_e() {
+        let c = Color::from_rgb(0.8, 0.3, 0.1);
+        assert_eq_float!(c.delta_e(&c), 0.0);
+        assert!(c.delta_e(&Color::from_rgb(0.81, 0.3, 0.1)) < 2.0);
+        let red = Color::from_rgb(1.0, 0.0, 0.0);
+        let green = Color::from_rgb(0.0, 1.0, 0.0);
+        assert!(red.delta_e(&green) > 100.0);
+    }
+
+    #[test]
+    fn test_mix_many() {
+        let red = Color::from_rgb(1.0, 0.0, 0.0);
+        let cyan = Color::from_rgb(0.0, 1.0, 1.0);
+        let gray = Color::mix_many(&[red, cyan], ColorSpace::RGB).unwrap();
+        assert_eq!(gray.space, ColorSpace::RGB);
+        assert_eq!(gray.components_3(), &[0.5, 0.5, 0.5]);
+        assert!(Color::mix_many(&[], ColorSpace::RGB).is_none());
+    }
+
+    #[test]
+    fn test_mix_many_circular_hue() {
+        let a = Color::from_hsv(0.9, 1.0, 1.0);
+        let b = Color::from_hsv(0.1, 1.0, 1.0);
+        let mixed = Color::mix_many(&[a, b], ColorSpace::HSV).unwrap();
+        // the average should wrap through 0.0, not land on 0.5
+        assert!(mixed.components[0] < 0.0001 || mixed.components[0] > 0.9999);
+        assert_eq_float!(mixed.components[1], 1.0);
+    }
+
+    #[test]
+    fn test_mix_many_mixed_spaces() {
+        let red = Color::from_hsl(0.0, 1.0, 0.5);
+        let blue = Color::from_hsv(2.0 / 3.0, 1.0, 1.0);
+        let green = Color::from_rgb(0.0, 1.0, 0.0);
+        let mixed = Color::mix_many(&[red, blue], ColorSpace::HSV).unwrap();
+        assert_eq!(mixed.space, ColorSpace::HSV);
+        // 0° and 240° average to 300° around the circle
+        assert!((mixed.components[0] - 5.0 / 6.0).abs() < 0.0001);
+        assert_eq_float!(mixed.components[1], 1.0);
+        assert_eq_float!(mixed.components[2], 1.0);
+
+        let mixed = Color::mix_many(&[red, green], ColorSpace::RGB).unwrap();
+        assert_eq!(mixed.components_3(), &[0.5, 0.5, 0.0]);
+        let mixed = Color::mix_many(&[red, blue, green], ColorSpace::HSL).unwrap();
+        assert_eq!(mixed.space, ColorSpace::HSL);
+    }
+
+    #[test]
+    fn test_builders() {
+        let c = Color::hsl_builder().h(0.5).s(1.0).l(0.4).build();
+        assert_eq!(c, Color::from_hsl(0.5, 1.0, 0.4));
+
+        let c = Color::hsv_builder().v(0.8).build();
+        assert_eq!(c.space, ColorSpace::HSV);
+        assert_eq!(c.components, [0.0, 0.0, 0.8, 1.0]);
+
+        let c = Color::rgb_builder().b(1.0).r(0.5).a(0.25).build();
+        assert_eq!(c, Color::from_rgba(0.5, 0.0, 1.0, 0.25));
+    }
+
+    #[test]
+    fn test_clip_to_gamut() {
+        // very saturated green, well outside sRGB
+        let lab = Color::from_lab(0.6, -1.2, 0.6);
+        let rgb = lab.to_rgb();
+        assert!(rgb.components_3().iter().any(|&c| !(0.0..=1.0).contains(&c)));
+
+        let clipped = lab.clip_to_gamut();
+        assert_eq!(clipped.space, ColorSpace::Lab);
+        let clipped_rgb = clipped.to_rgb();
+        assert!(clipped_rgb.components_3().iter().all(|&c| (0.0..=1.0).contains(&c)));
+
+        // hue angle and lightness in Lab are preserved
+        let hue = |c: &Color| c.components[2].atan2(c.components[1]);
+        assert!((hue(&clipped) - hue(&lab)).abs() < 0.01);
+        assert!((clipped.components[0] - lab.components[0]).abs() < 0.01);
+
+        let in_gamut = Color::from_rgb(0.2, 0.4, 0.6);
+        assert_eq!(in_gamut.clip_to_gamut(), in_gamut);
+    }
+
+    #[test]
+    fn test_bytes() {
+        assert_eq!(Color::from_rgba(1.0, 0.5, 0.0, 0.25).as_bytes(), [255, 128, 0, 64]);
+        assert_eq!(Color::from_hsv(0.0, 1.0, 1.0).as_bytes(), [255, 0, 0, 255]);
+        assert_eq!(Color::from_rgb(2.0, -1.0, 0.0).as_bytes(), [255, 0, 0, 255]);
+        assert_eq!(Color::from_bytes([255, 128, 0, 64]).as_bytes(), [255, 128, 0, 64]);
+    }
+
+    #[test]
+    fn test_packed_u32() {
+        let c = Color::from_bytes([0x12, 0x34, 0x56, 0x78]);
+        assert_eq!(c.to_u32_argb(), 0x78123456);
+        assert_eq!(c.to_u32_rgba(), 0x12345678);
+        assert_eq!(Color::from_u32_argb(0x78123456), c);
+        assert_eq!(Color::from_u32_rgba(0x12345678), c);
+
+        let translucent = Color::from_rgba(0.2, 0.4, 0.6, 0.5);
+        let argb = Color::from_u32_argb(translucent.to_u32_argb());
+        let rgba = Color::from_u32_rgba(translucent.to_u32_rgba());
+        for i in 0..4 {
+            assert!((argb.components[i] - translucent.components[i]).abs() <= 1.0 / 255.0);
+            assert_eq!(argb.components[i], rgba.components[i]);
+        }
+        assert_eq!(argb.check_alpha(), Some(128.0 / 255.0));
+    }
+
+    #[test]
+    fn test_adapt_white_point() {
+        let c = Color::from_rgb(0.8, 0.4, 0.2);
+        let d50 = c.adapt_white_point(WhitePoint::D65, WhitePoint::D50);
+        assert_eq!(d50.space, ColorSpace::RGB);
+        // D50 is warmer, so adapting to it shifts toward red and away from blue
+        assert!(d50.components[0] > c.components[0]);
+        assert!(d50.components[2] < c.components[2]);
+        let back = d50.adapt_white_point(WhitePoint::D50, WhitePoint::D65);
+        for i in 0..3 {
+            assert_eq_float!(back.components[i], c.components[i]);
+        }
+
+        // the source white maps to the destination white, which is white again in sRGB
+        let white = Color::from_rgb(1.0, 1.0, 1.0);
+        let adapted = white.adapt_white_point(WhitePoint::A, WhitePoint::D65);
+        let xyz = linear_rgb_to_xyz(adapted.components_3().map(srgb_to_linear));
+        let expected = mat3_mul(&BRADFORD_INV, {
+            let (cone_a, cone_d65, cone_w) = (mat3_mul(&BRADFORD, WhitePoint::A.xyz()), mat3_mul(&BRADFORD, D65_WHITE), mat3_mul(&BRADFORD, D65_WHITE));
+            [0, 1, 2].map(|i| cone_w[i] * cone_d65[i] / cone_a[i])
+        });
+        for i in 0..3 {
+            assert_eq_float!(xyz[i], expected[i]);
+        }
+
+        let hsl = Color::from_hsla(0.3, 0.5, 0.5, 0.25);
+        let adapted = hsl.adapt_white_point(WhitePoint::D65, WhitePoint::D75);
+        assert_eq!(adapted.space, ColorSpace::HSLA);
+        assert_eq_float!(adapted.alpha(), 0.25);
+    }
+
+    #[test]
+    fn test_mix() {
+        let black = Color::from_rgb(0.0, 0.0, 0.0);
+        let white = Color::from_rgb(1.0, 1.0, 1.0);
+        let mid = black.mix(&white, 0.5);
+        assert_eq!(mid.space, ColorSpace::RGBA);
+        for i in 0..3 {
+            assert_eq_float!(srgb_to_linear(mid.components[i]), 0.5);
+            // noticeably lighter than the naive gamma-space midpoint
+            assert!((mid.components[i] - 0.7354).abs() < 0.001);
+        }
+
+        let red = Color::from_rgba(1.0, 0.0, 0.0, 1.0);
+        let blue = Color::from_hsva(2.0 / 3.0, 1.0, 1.0, 0.0);
+        let (start, end) = (red.mix(&blue, 0.0), red.mix(&blue, 1.0));
+        for i in 0..4 {
+            assert_eq_float!(start.components[i], red.components[i]);
+            assert_eq_float!(end.components[i], blue.to_rgb().components[i]);
+        }
+        assert_eq_float!(red.mix(&blue, 0.25).alpha(), 0.75);
+    }
+
+    #[test]
+    fn test_apply_curve() {
+        let line = |from: f32, to: f32| {
+            let mut curve = FloatCurve::new();
+            curve.add_point(0.0, from, to - from, to - from);
+            curve.add_point(1.0, to, to - from, to - from);
+            curve
+        };
+        let (identity, invert) = (line(0.0, 1.0), line(1.0, 0.0));
+
+        let c = Color::from_rgba(0.2, 0.5, 0.9, 0.4);
+        let same = c.apply_curve(&identity);
+        let inverted = c.apply_curve(&invert);
+        assert_eq!(same.space, ColorSpace::RGBA);
+        for i in 0..3 {
+            assert_eq_float!(same.components[i], c.components[i]);
+            assert_eq_float!(inverted.components[i], 1.0 - c.components[i]);
+        }
+        assert_eq!(inverted.alpha(), 0.4);
+
+        let per_channel = c.apply_channel_curves(&invert, &identity, &line(0.5, 0.5));
+        assert_eq_float!(per_channel.components[0], 0.8);
+        assert_eq_float!(per_channel.components[1], 0.5);
+        assert_eq_float!(per_channel.components[2], 0.5);
+
+        // non-RGB colors keep their space
+        let hsl = Color::from_hsl(0.6, 0.5, 0.5);
+        let hsl_inverted = hsl.apply_curve(&invert);
+        assert_eq!(hsl_inverted.space, ColorSpace::HSL);
+        let (original, inverted) = (hsl.to_rgb(), hsl_inverted.to_rgb());
+        for i in 0..3 {
+            assert_eq_float!(inverted.components[i], 1.0 - original.components[i]);
+        }
+    }
+
+    #[test]
+    fn test_median_cut() {
+        let mut colors = Vec::new();
+        for i in 0..20 {
+            let jitter = i as f32 * 0.005;
+            colors.push(Color::from_rgb(0.9 + jitter, 0.05, jitter));
+            colors.push(Color::from_rgb(jitter, 0.1, 0.85 + jitter));
+        }
+        let mut palette = Color::median_cut(&colors, 2);
+        assert_eq!(palette.len(), 2);
+        palette.sort_by(|a, b| b.r().total_cmp(&a.r()));
+        let (red, blue) = (palette[0], palette[1]);
+        assert!(red.r() > 0.9 && red.b() < 0.1, "{:?}", red);
+        assert!(blue.b() > 0.85 && blue.r() < 0.1, "{:?}", blue);
+
+        // more palette slots than distinct colors just returns the distinct colors
+        let few = [Color::from_rgb(1.0, 0.0, 0.0), Color::from_hsl(0.0, 1.0, 0.5), Color::from_rgb(0.0, 1.0, 0.0)];
+        let palette = Color::median_cut(&few, 8);
+        assert_eq!(palette.len(), 2);
+        assert!(palette.iter().all(|c| c.space == ColorSpace::RGBA));
+
+        assert!(Color::median_cut(&[], 4).is_empty());
+        assert!(Color::median_cut(&few, 0).is_empty());
+        assert_eq!(Color::median_cut(&colors, 7).len(), 7);
+    }
+
+    #[test]
+    fn test_simulate_cvd() {
+        let green = Color::from_rgb(0.0, 1.0, 0.0);
+        let red = Color::from_rgb(1.0, 0.0, 0.0);
+        let deutan_green = green.simulate_cvd(Cvd::Deuteranopia);
+        assert_eq!(deutan_green.space, ColorSpace::RGB);
+        // pure green becomes a yellowish olive, with red and green nearly equal and no blue
+        let [r, g, b] = *deutan_green.components_3();
+        assert!(r > 0.5 && (r - g).abs() < 0.01 && b < 0.2, "{:?}", deutan_green);
+        // and red and green become hard to tell apart
+        assert!(red.simulate_cvd(Cvd::Deuteranopia).hue_distance(&deutan_green) < 0.05);
+        assert!(red.simulate_cvd(Cvd::Protanopia).hue_distance(&green.simulate_cvd(Cvd::Protanopia)) < 0.05);
+        assert!(red.hue_distance(&green) > 0.3);
+
+        // white and grays are unaffected
+        let gray = Color::from_rgba(0.5, 0.5, 0.5, 0.25);
+        for kind in [Cvd::Protanopia, Cvd::Deuteranopia, Cvd::Tritanopia] {
+            let simulated = gray.simulate_cvd(kind);
+            for i in 0..4 {
+                assert!((simulated.components[i] - gray.components[i]).abs() < 0.001);
+            }
+        }
+
+        let hsl = Color::from_hsl(0.4, 0.7, 0.4);
+        assert_eq!(hsl.simulate_cvd(Cvd::Normal), hsl);
+        assert_eq!(hsl.simulate_cvd(Cvd::Tritanopia).space, ColorSpace::HSL);
+    }
+
+    #[test]
+    fn test_over() {
+        let red = Color::from_rgb(1.0, 0.0, 0.0);
+        let blue = Color::from_rgba(0.0, 0.0, 1.0, 0.5);
+
+        assert_eq!(red.over(&blue), Color::from_rgba(1.0, 0.0, 0.0, 1.0));
+        assert_eq!(red.transparent().over(&blue), blue);
+
+        let half_white = Color::from_rgba(1.0, 1.0, 1.0, 0.5);
+        let mid = half_white.over(&Color::black(ColorSpace::RGB));
+        assert_eq!(mid.space, ColorSpace::RGBA);
+        assert_eq_float!(mid.components[0], 0.5);
+        assert_eq_float!(mid.components[1], 0.5);
+        assert_eq_float!(mid.components[2], 0.5);
+        assert_eq_float!(mid.alpha(), 1.0);
+
+        // translucent over translucent
+        let out = half_white.over(&blue);
+        assert_eq_float!(out.alpha(), 0.75);
+        assert_eq_float!(out.components[0], 2.0 / 3.0);
+        assert_eq_float!(out.components[2], 1.0);
+    }
+}
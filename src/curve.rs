@@ -1,15 +1,79 @@
+use std::fmt::{Display, Formatter};
+use std::ops::Index;
 use crate::lerp;
 
+/// Controls how a point's tangents are calculated when the curve recomputes them.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum TangentMode {
+    /// Both tangents match the slope between the neighboring points. Ends of the curve are flat.
+    #[default]
+    Auto,
+    /// Both tangents are zero.
+    Flat,
+    /// Each tangent matches the slope of the straight line to the neighboring point on that side.
+    Linear,
+    /// Tangents are set manually and never recomputed.
+    Broken,
+}
+
+impl TangentMode {
+    fn to_byte(self) -> u8 {
+        match self {
+            TangentMode::Auto => 0,
+            TangentMode::Flat => 1,
+            TangentMode::Linear => 2,
+            TangentMode::Broken => 3,
+        }
+    }
+
+    fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            0 => Some(TangentMode::Auto),
+            1 => Some(TangentMode::Flat),
+            2 => Some(TangentMode::Linear),
+            3 => Some(TangentMode::Broken),
+            _ => None
+        }
+    }
+}
+
+/// Error returned by `FloatCurve::from_bytes`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CurveDecodeError {
+    /// The input ended before all points were read.
+    Truncated,
+    /// There were extra bytes after the last point.
+    TrailingBytes,
+    /// A point had an unknown tangent mode.
+    InvalidTangentMode(u8),
+    /// The point at the given index comes before the previous point in time.
+    Unsorted(usize),
+}
+
+impl Display for CurveDecodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CurveDecodeError::Truncated => write!(f, "curve data is truncated"),
+            CurveDecodeError::TrailingBytes => write!(f, "curve data has trailing bytes"),
+            CurveDecodeError::InvalidTangentMode(b) => write!(f, "invalid tangent mode {}", b),
+            CurveDecodeError::Unsorted(i) => write!(f, "point {} is out of time order", i),
+        }
+    }
+}
+
+impl std::error::Error for CurveDecodeError {}
+
 #[derive(Debug, Copy, Clone, Default)]
 pub struct FloatCurvePoint {
     pub time: f32,
     pub value: f32,
     pub arrive_tangent: f32,
     pub leave_tangent: f32,
+    pub mode: TangentMode,
 }
 impl FloatCurvePoint {
     pub fn new(time: f32, value: f32, arrive_tangent: f32, leave_tangent: f32) -> Self {
-        Self { time, value, arrive_tangent, leave_tangent }
+        Self { time, value, arrive_tangent, leave_tangent, mode: TangentMode::Auto }
     }
 }
 
@@ -18,28 +82,72 @@ impl FloatCurvePoint {
 #[derive(Debug, Clone, Default)]
 pub struct FloatCurve {
     points: Vec<FloatCurvePoint>,
+    // named events, sorted by time. events with the same time are kept in insertion order
+    events: Vec<(f32, String)>,
 }
 
 impl FloatCurve {
     pub fn new() -> Self {
         Self {
             points: Vec::new(),
+            events: Vec::new(),
         }
     }
 
+    /// Returns the curve's points, sorted by time. Use the `FloatCurve` methods to modify them so
+    /// they stay sorted.
+    pub fn points(&self) -> &[FloatCurvePoint] {
+        &self.points
+    }
+
+    /// Iterates over the curve's points in time order.
+    pub fn iter(&self) -> std::slice::Iter<'_, FloatCurvePoint> {
+        self.points.iter()
+    }
+
+    /// Attaches a named event to the curve at `time`, e.g. a footstep in an animation.
+    /// Events don't affect the curve's value.
+    pub fn add_event(&mut self, time: f32, name: impl Into<String>) {
+        let idx = self.events.partition_point(|(t, _)| *t <= time);
+        self.events.insert(idx, (time, name.into()));
+    }
+
+    /// Returns the names of all events with times in `(from, to]`, in time order. Calling this
+    /// with consecutive time ranges while playing back reports each event exactly once. Returns
+    /// nothing if `from >= to`.
+    pub fn events_between(&self, from: f32, to: f32) -> Vec<&str> {
+        self.events.iter()
+            .filter(|(time, _)| *time > from && *time <= to)
+            .map(|(_, name)| name.as_str())
+            .collect()
+    }
+
     /// returns index of new element
     pub fn add_point_auto(&mut self, time: f32, value: f32) -> usize {
         let idx = self.add_point(time, value, 0.0, 0.0);
-        self.calc_auto_tangent_for_point(idx);
+        self.calc_tangents_for_point(idx);
         if idx > 0 {
-            self.calc_auto_tangent_for_point(idx-1);
+            self.calc_tangents_for_point(idx-1);
         }
         if idx < self.points.len()-1 {
-            self.calc_auto_tangent_for_point(idx+1);
+            self.calc_tangents_for_point(idx+1);
         }
         idx
     }
 
+    /// Sets the tangent mode of the point at `idx` and recomputes its tangents.
+    pub fn set_point_tangent_mode(&mut self, idx: usize, mode: TangentMode) {
+        self.points[idx].mode = mode;
+        self.calc_tangents_for_point(idx);
+    }
+
+    /// Recomputes the tangents of every point according to its tangent mode.
+    pub fn recalculate_tangents(&mut self) {
+        for i in 0..self.points.len() {
+            self.calc_tangents_for_point(i);
+        }
+    }
+
     /// returns index of new element
     pub fn add_point(&mut self, time: f32, value: f32, arrive_tangent: f32, leave_tangent: f32) -> usize {
         if self.points.is_empty() {
@@ -91,6 +199,206 @@ impl FloatCurve {
         }
     }
 
+    /// Moves the point at `idx` to a new time, keeping the points sorted. If other points already
+    /// have `new_time`, the moved point goes after them. Tangents of the moved point and of its old
+    /// and new neighbors are recomputed according to their tangent modes, which are kept as-is.
+    /// Returns the new index of the moved point.
+    pub fn set_point_time(&mut self, idx: usize, new_time: f32) -> usize {
+        let p = self.points.remove(idx);
+        // after the removal, the old neighbors sit at idx-1 and idx
+        let old_neighbors = [idx.checked_sub(1), (idx < self.points.len()).then_some(idx)];
+        let new_idx = self.points.partition_point(|q| q.time <= new_time);
+        self.points.insert(new_idx, FloatCurvePoint { time: new_time, ..p });
+
+        let old_neighbors = old_neighbors.into_iter().flatten().map(|i| if i >= new_idx { i + 1 } else { i });
+        let new_neighbors = [new_idx.checked_sub(1), Some(new_idx), Some(new_idx + 1)].into_iter().flatten();
+        for i in old_neighbors.chain(new_neighbors) {
+            if i < self.points.len() {
+                self.calc_tangents_for_point(i);
+            }
+        }
+        new_idx
+    }
+
+    /// Sets the value of the point at `idx`. Tangents of the point and its neighbors are recomputed
+    /// according to their tangent modes.
+    pub fn set_point_value(&mut self, idx: usize, value: f32) {
+        self.points[idx].value = value;
+        for i in idx.saturating_sub(1)..=idx + 1 {
+            if i < self.points.len() {
+                self.calc_tangents_for_point(i);
+            }
+        }
+    }
+
+    /// Encodes the curve in a compact binary format: the number of points as a little-endian `u32`,
+    /// followed by each point's time, value, arrive tangent, and leave tangent as little-endian
+    /// `f32`s and its tangent mode as a single byte. Events are not included.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(4 + self.points.len() * POINT_BYTES);
+        bytes.extend_from_slice(&(self.points.len() as u32).to_le_bytes());
+        for p in &self.points {
+            for f in [p.time, p.value, p.arrive_tangent, p.leave_tangent] {
+                bytes.extend_from_slice(&f.to_le_bytes());
+            }
+            bytes.push(p.mode.to_byte());
+        }
+        bytes
+    }
+
+    /// Decodes a curve produced by `to_bytes`. Fails if the data is truncated or has extra bytes,
+    /// or if the points aren't sorted by time.
+    pub fn from_bytes(bytes: &[u8]) -> Result<FloatCurve, CurveDecodeError> {
+        let read_u32 = |at: usize| -> Result<[u8; 4], CurveDecodeError> {
+            bytes.get(at..at+4).map(|b| b.try_into().unwrap()).ok_or(CurveDecodeError::Truncated)
+        };
+        let count = u32::from_le_bytes(read_u32(0)?) as usize;
+        if (bytes.len() - 4) / POINT_BYTES < count {
+            return Err(CurveDecodeError::Truncated);
+        }
+        if bytes.len() != 4 + count * POINT_BYTES {
+            return Err(CurveDecodeError::TrailingBytes);
+        }
+
+        let mut points: Vec<FloatCurvePoint> = Vec::with_capacity(count);
+        for i in 0..count {
+            let at = 4 + i * POINT_BYTES;
+            let f = |n: usize| read_u32(at + n * 4).map(f32::from_le_bytes);
+            let mode_byte = bytes[at + 16];
+            let mode = TangentMode::from_byte(mode_byte).ok_or(CurveDecodeError::InvalidTangentMode(mode_byte))?;
+            let point = FloatCurvePoint { time: f(0)?, value: f(1)?, arrive_tangent: f(2)?, leave_tangent: f(3)?, mode };
+            if i > 0 && point.time < points[i-1].time {
+                return Err(CurveDecodeError::Unsorted(i));
+            }
+            points.push(point);
+        }
+        Ok(FloatCurve { points, events: Vec::new() })
+    }
+
+    /// Shifts every point and event of the curve by `delta` in time.
+    pub fn offset_time(&mut self, delta: f32) {
+        for p in &mut self.points {
+            p.time += delta;
+        }
+        for (time, _) in &mut self.events {
+            *time += delta;
+        }
+    }
+
+    /// Appends a copy of `other`'s points after the last point of this curve, shifted so that
+    /// `other`'s first point lands `gap` after this curve's end. Tangents of the two points on either
+    /// side of the join are recomputed according to their tangent modes, since they're no longer
+    /// the ends of their curves. If this curve is empty, `other`'s points are copied unshifted.
+    /// `other`'s events are copied along with its points. A negative `gap` is treated as zero, since
+    /// overlapping the curves would break the time ordering of the points.
+    pub fn append(&mut self, other: &FloatCurve, gap: f32) {
+        if other.points.is_empty() { return }
+        let join = self.points.len();
+        let offset = match self.points.last() {
+            Some(last) => last.time + gap.max(0.0) - other.points[0].time,
+            None => 0.0,
+        };
+        self.points.extend(other.points.iter().map(|p| FloatCurvePoint { time: p.time + offset, ..*p }));
+        for (time, name) in &other.events {
+            self.add_event(time + offset, name.clone());
+        }
+        if join > 0 {
+            self.calc_tangents_for_point(join - 1);
+            self.calc_tangents_for_point(join);
+        }
+    }
+
+    /// Rounds every point's time to the nearest multiple of `time_step` and its value to the nearest
+    /// multiple of `value_step`. A step of zero or less leaves that axis unchanged. If several points
+    /// snap to the same time, only the earliest of them (by original time) is kept. Tangents are
+    /// recomputed only for points whose own key, a neighbor's key, or whose neighbors changed, so
+    /// explicitly set tangents elsewhere are left alone.
+    pub fn snap_points(&mut self, time_step: f32, value_step: f32) {
+        let snap = |v: f32, step: f32| if step > 0.0 { (v / step).round() * step } else { v };
+        let mut moved = Vec::with_capacity(self.points.len());
+        for p in &mut self.points {
+            let (time, value) = (snap(p.time, time_step), snap(p.value, value_step));
+            moved.push(time != p.time || value != p.value);
+            p.time = time;
+            p.value = value;
+        }
+        // points were sorted before snapping and rounding is monotonic, so they still are.
+        // remember the original index of each point that's kept, to tell which neighbors went away
+        let mut kept = Vec::with_capacity(self.points.len());
+        let (mut index, mut last_time) = (0, None);
+        self.points.retain(|p| {
+            let keep = last_time != Some(p.time);
+            if keep {
+                kept.push(index);
+                last_time = Some(p.time);
+            }
+            index += 1;
+            keep
+        });
+
+        for k in 0..kept.len() {
+            let prev = k.checked_sub(1).map(|j| kept[j]);
+            let next = kept.get(k + 1).copied().unwrap_or(moved.len());
+            let neighbors_removed = prev.is_some_and(|prev| prev + 1 != kept[k]) || kept[k] + 1 != next;
+            let keys_moved = moved[kept[k]] || prev.is_some_and(|prev| moved[prev]) || moved.get(next).copied().unwrap_or(false);
+            if neighbors_removed || keys_moved {
+                self.calc_tangents_for_point(k);
+            }
+        }
+    }
+
+    /// Recomputes every point's tangents with the Fritsch–Carlson method, which guarantees that a
+    /// monotonic run of keys produces a monotonic curve (no overshoot between keys). Points are
+    /// switched to `TangentMode::Broken` so the tangents aren't replaced by later recalculations.
+    pub fn calc_monotone_tangents(&mut self) {
+        let n = self.points.len();
+        if n < 2 {
+            for p in &mut self.points {
+                p.arrive_tangent = 0.0;
+                p.leave_tangent = 0.0;
+                p.mode = TangentMode::Broken;
+            }
+            return;
+        }
+
+        let secants: Vec<f32> = self.points.windows(2)
+            .map(|pair| (pair[1].value - pair[0].value) / (pair[1].time - pair[0].time).max(0.00001))
+            .collect();
+
+        let mut tangents = vec![0.0; n];
+        tangents[0] = secants[0];
+        tangents[n-1] = secants[n-2];
+        for i in 1..n-1 {
+            // local extrema and plateaus get flat tangents
+            if secants[i-1] * secants[i] > 0.0 {
+                tangents[i] = (secants[i-1] + secants[i]) * 0.5;
+            }
+        }
+
+        for (i, &secant) in secants.iter().enumerate() {
+            if secant == 0.0 {
+                tangents[i] = 0.0;
+                tangents[i+1] = 0.0;
+                continue;
+            }
+            let alpha = tangents[i] / secant;
+            let beta = tangents[i+1] / secant;
+            // keep (alpha, beta) inside the circle of radius 3, which is sufficient for monotonicity
+            let dist_sq = alpha * alpha + beta * beta;
+            if dist_sq > 9.0 {
+                let tau = 3.0 / dist_sq.sqrt();
+                tangents[i] = tau * alpha * secant;
+                tangents[i+1] = tau * beta * secant;
+            }
+        }
+
+        for (p, tangent) in self.points.iter_mut().zip(tangents) {
+            p.arrive_tangent = tangent;
+            p.leave_tangent = tangent;
+            p.mode = TangentMode::Broken;
+        }
+    }
+
     pub fn clear_points(&mut self) {
         self.points.clear();
     }
@@ -139,6 +447,71 @@ impl FloatCurve {
         solve_two_points(self.points[i-1], self.points[i], time)
     }
 
+    /// Builds a new curve with `count` keys evenly spaced between the first and last points of this
+    /// curve, using auto tangents. If `count` is 1 or this curve only has one point, the new curve
+    /// has a single key at the start. Empty curves or a `count` of 0 produce an empty curve.
+    pub fn resample(&self, count: usize) -> FloatCurve {
+        let mut result = FloatCurve::new();
+        if self.points.is_empty() || count == 0 { return result }
+
+        let start = self.points[0].time;
+        let end = self.points[self.points.len()-1].time;
+        if count == 1 || end - start < 0.000_001 {
+            result.add_point_auto(start, self.get_value(start));
+            return result;
+        }
+
+        let step = (end - start) / (count - 1) as f32;
+        for i in 0..count {
+            let time = start + step * i as f32;
+            result.add_point_auto(time, self.get_value(time));
+        }
+        result
+    }
+
+    /// Approximates the area under the curve between `from` and `to` using the trapezoidal rule
+    /// over `samples` evenly spaced intervals. If `from > to` the result is negated.
+    pub fn integrate(&self, from: f32, to: f32, samples: usize) -> f32 {
+        if self.points.is_empty() { return 0.0 }
+        if from > to { return -self.integrate(to, from, samples) }
+
+        let samples = samples.max(1);
+        let step = (to - from) / samples as f32;
+        let mut sum = (self.get_value(from) + self.get_value(to)) * 0.5;
+        for i in 1..samples {
+            sum += self.get_value(from + step * i as f32);
+        }
+        sum * step
+    }
+
+    /// Returns true if the curve goes above or below the values of the two keys on either side
+    /// anywhere between them, e.g. when steep auto tangents make the curve overshoot a key. Checks
+    /// `samples` evenly spaced times strictly between each pair of keys.
+    pub fn has_overshoot(&self, samples: usize) -> bool {
+        self.points.windows(2).any(|pair| {
+            let (a, b) = (pair[0], pair[1]);
+            if b.time - a.time < 0.000_001 { return false }
+            let (min, max) = (a.value.min(b.value), a.value.max(b.value));
+            (1..=samples).any(|i| {
+                let time = a.time + (b.time - a.time) * i as f32 / (samples + 1) as f32;
+                let value = solve_two_points(a, b, time);
+                value < min - 0.000_01 || value > max + 0.000_01
+            })
+        })
+    }
+
+    fn calc_tangents_for_point(&mut self, i: usize) {
+        match self.points[i].mode {
+            TangentMode::Auto => self.calc_auto_tangent_for_point(i),
+            TangentMode::Flat => {
+                self.points[i].leave_tangent = 0.0;
+                self.points[i].arrive_tangent = 0.0;
+            }
+            TangentMode::Linear => self.calc_linear_tangent_for_point(i),
+            TangentMode::Broken => {}
+        }
+    }
+
     fn calc_auto_tangent_for_point(&mut self, i: usize) {
         if i == 0 || i == self.points.len() - 1 {
             // ends are flat
@@ -155,8 +528,29 @@ impl FloatCurve {
             self.points[i].arrive_tangent = slope;
         }
     }
+
+    fn calc_linear_tangent_for_point(&mut self, i: usize) {
+        let slope_between = |a: FloatCurvePoint, b: FloatCurvePoint| (b.value-a.value)/(b.time-a.time).max(0.00001);
+        let arrive = if i > 0 { Some(slope_between(self.points[i-1], self.points[i])) } else { None };
+        let leave = if i < self.points.len() - 1 { Some(slope_between(self.points[i], self.points[i+1])) } else { None };
+        // at the ends, continue the slope of the only neighboring segment
+        self.points[i].arrive_tangent = arrive.or(leave).unwrap_or(0.0);
+        self.points[i].leave_tangent = leave.or(arrive).unwrap_or(0.0);
+    }
+}
+
+/// Returns the point at the given index, in time order. Panics if the index is out of bounds.
+impl Index<usize> for FloatCurve {
+    type Output = FloatCurvePoint;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.points[index]
+    }
 }
 
+// 4 f32s plus a tangent mode byte
+const POINT_BYTES: usize = 17;
+
 fn solve_two_points(a: FloatCurvePoint, b: FloatCurvePoint, time: f32) -> f32 {
     let diff = b.time - a.time;
     let alpha = (time - a.time) / diff;
@@ -177,3 +571,417 @@ fn interp_bezier_points(p0: f32, p1: f32, p2: f32, p3: f32, alpha: f32) -> f32 {
     let p123 = lerp(p12, p23, alpha);
     lerp(p012, p123, alpha)
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::{CurveDecodeError, FloatCurve, TangentMode};
+
+    fn three_point_curve() -> FloatCurve {
+        let mut curve = FloatCurve::new();
+        curve.add_point_auto(0.0, 0.0);
+        curve.add_point_auto(1.0, 1.0);
+        curve.add_point_auto(2.0, 3.0);
+        curve
+    }
+
+    #[test]
+    fn test_tangent_mode_auto() {
+        let curve = three_point_curve();
+        assert_eq!(curve.points[1].arrive_tangent, 1.5);
+        assert_eq!(curve.points[1].leave_tangent, 1.5);
+        assert_eq!(curve.points[0].leave_tangent, 0.0);
+    }
+
+    #[test]
+    fn test_tangent_mode_flat() {
+        let auto = three_point_curve();
+        let mut flat = three_point_curve();
+        flat.set_point_tangent_mode(1, TangentMode::Flat);
+        assert_eq!(flat.points[1].arrive_tangent, 0.0);
+        assert_eq!(flat.points[1].leave_tangent, 0.0);
+        // a flat key eases in, so approaching it from below stays lower than with auto tangents
+        assert!(flat.get_value(0.75) > auto.get_value(0.75));
+        assert!(flat.get_value(1.25) < auto.get_value(1.25));
+    }
+
+    #[test]
+    fn test_tangent_mode_linear() {
+        let mut curve = three_point_curve();
+        for i in 0..3 {
+            curve.set_point_tangent_mode(i, TangentMode::Linear);
+        }
+        assert!((curve.get_value(0.5) - 0.5).abs() < 0.001);
+        assert!((curve.get_value(1.5) - 2.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_tangent_mode_broken() {
+        let mut curve = three_point_curve();
+        curve.points[1].arrive_tangent = 0.0;
+        curve.points[1].leave_tangent = 4.0;
+        curve.set_point_tangent_mode(1, TangentMode::Broken);
+        let before = curve.get_value(0.5);
+        curve.add_point_auto(3.0, 3.0); // recomputes neighbor tangents
+        curve.recalculate_tangents();
+        assert_eq!(curve.points[1].arrive_tangent, 0.0);
+        assert_eq!(curve.points[1].leave_tangent, 4.0);
+        assert_eq!(curve.get_value(0.5), before);
+    }
+
+    #[test]
+    fn test_set_point_time() {
+        let mut curve = FloatCurve::new();
+        curve.add_point(0.0, 0.0, 0.0, 0.0);
+        curve.add_point(1.0, 10.0, 0.0, 0.0);
+        curve.add_point(2.0, 20.0, 0.0, 0.0);
+
+        // move the middle point past its right neighbor
+        let idx = curve.set_point_time(1, 3.0);
+        assert_eq!(idx, 2);
+        assert_eq!(curve.get_value(3.0), 10.0);
+        assert_eq!(curve.get_value(2.0), 20.0);
+
+        // and back to the front
+        let idx = curve.set_point_time(2, -1.0);
+        assert_eq!(idx, 0);
+        assert_eq!(curve.get_value(-1.0), 10.0);
+    }
+
+    #[test]
+    fn test_set_point_time_onto_neighbor() {
+        let mut curve = FloatCurve::new();
+        curve.add_point_auto(0.0, 0.0);
+        curve.add_point_auto(1.0, 10.0);
+        curve.add_point_auto(2.0, 20.0);
+
+        // moving a key onto another key's time puts it after that key
+        let idx = curve.set_point_time(1, 0.0);
+        assert_eq!(idx, 1);
+        let times: Vec<f32> = curve.iter().map(|p| p.time).collect();
+        assert_eq!(times, vec![0.0, 0.0, 2.0]);
+        assert_eq!(curve[1].value, 10.0);
+
+        let idx = curve.set_point_time(0, 2.0);
+        assert_eq!(idx, 2);
+        assert_eq!(curve[2].value, 0.0);
+        assert_eq!(curve[1].time, 2.0);
+    }
+
+    #[test]
+    fn test_set_point_time_keeps_mode() {
+        let mut curve = FloatCurve::new();
+        for (time, value) in [(0.0, 0.0), (1.0, 1.0), (2.0, 4.0), (3.0, 9.0)] {
+            curve.add_point_auto(time, value);
+        }
+        curve.calc_monotone_tangents();
+        let tangent = curve[1].leave_tangent;
+        let idx = curve.set_point_time(1, 2.5);
+        assert_eq!(curve[idx].mode, TangentMode::Broken);
+        assert_eq!(curve[idx].leave_tangent, tangent);
+
+        curve.set_point_tangent_mode(0, TangentMode::Flat);
+        let idx = curve.set_point_time(0, 4.0);
+        assert_eq!(idx, 3);
+        assert_eq!(curve[idx].mode, TangentMode::Flat);
+        assert_eq!((curve[idx].arrive_tangent, curve[idx].leave_tangent), (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_set_point_time_updates_neighbor_tangents() {
+        let mut curve = FloatCurve::new();
+        for (time, value) in [(0.0, 0.0), (1.0, 1.0), (2.0, 4.0), (3.0, 9.0)] {
+            curve.add_point_auto(time, value);
+        }
+        curve.set_point_time(2, 5.0);
+        let mut expected = FloatCurve::new();
+        for (time, value) in [(0.0, 0.0), (1.0, 1.0), (3.0, 9.0), (5.0, 4.0)] {
+            expected.add_point_auto(time, value);
+        }
+        for (a, b) in curve.iter().zip(expected.iter()) {
+            assert_eq!((a.time, a.value, a.arrive_tangent, a.leave_tangent), (b.time, b.value, b.arrive_tangent, b.leave_tangent));
+        }
+    }
+
+    #[test]
+    fn test_integrate_constant() {
+        let mut curve = FloatCurve::new();
+        curve.add_point(0.0, 3.0, 0.0, 0.0);
+        curve.add_point(1.0, 3.0, 0.0, 0.0);
+        assert!((curve.integrate(0.0, 4.0, 16) - 12.0).abs() < 0.0001);
+        assert!((curve.integrate(4.0, 0.0, 16) + 12.0).abs() < 0.0001);
+        assert_eq!(FloatCurve::new().integrate(0.0, 4.0, 16), 0.0);
+    }
+
+    #[test]
+    fn test_resample() {
+        let mut curve = FloatCurve::new();
+        curve.add_point_auto(0.0, 0.0);
+        curve.add_point_auto(0.1, 0.2);
+        curve.add_point_auto(0.2, 0.5);
+        curve.add_point_auto(2.0, 1.0);
+        let resampled = curve.resample(41);
+        assert_eq!(resampled.points.len(), 41);
+        assert_eq!(resampled.points[0].time, 0.0);
+        assert!((resampled.points[40].time - 2.0).abs() < 0.0001);
+        for i in 0..=20 {
+            let t = i as f32 * 0.1;
+            assert!((resampled.get_value(t) - curve.get_value(t)).abs() < 0.05);
+        }
+    }
+
+    #[test]
+    fn test_resample_degenerate() {
+        assert!(FloatCurve::new().resample(10).points.is_empty());
+        let mut curve = FloatCurve::new();
+        curve.add_point_auto(1.0, 3.0);
+        assert!(curve.resample(0).points.is_empty());
+        let single = curve.resample(10);
+        assert_eq!(single.points.len(), 1);
+        assert_eq!(single.get_value(1.0), 3.0);
+    }
+
+    #[test]
+    fn test_bytes_round_trip() {
+        let mut curve = three_point_curve();
+        curve.set_point_tangent_mode(2, TangentMode::Flat);
+        let bytes = curve.to_bytes();
+        assert_eq!(bytes.len(), 4 + 3 * 17);
+        let decoded = FloatCurve::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.points.len(), 3);
+        for (a, b) in curve.points.iter().zip(decoded.points.iter()) {
+            assert_eq!((a.time, a.value, a.arrive_tangent, a.leave_tangent, a.mode), (b.time, b.value, b.arrive_tangent, b.leave_tangent, b.mode));
+        }
+        assert_eq!(FloatCurve::from_bytes(&FloatCurve::new().to_bytes()).unwrap().points.len(), 0);
+    }
+
+    #[test]
+    fn test_bytes_decode_errors() {
+        let bytes = three_point_curve().to_bytes();
+        assert_eq!(FloatCurve::from_bytes(&bytes[..bytes.len()-1]).unwrap_err(), CurveDecodeError::Truncated);
+        assert_eq!(FloatCurve::from_bytes(&bytes[..2]).unwrap_err(), CurveDecodeError::Truncated);
+        let mut extra = bytes.clone();
+        extra.push(0);
+        assert_eq!(FloatCurve::from_bytes(&extra).unwrap_err(), CurveDecodeError::TrailingBytes);
+
+        // swap the times of the first two points
+        let mut unsorted = bytes.clone();
+        unsorted[4..8].copy_from_slice(&5.0f32.to_le_bytes());
+        assert_eq!(FloatCurve::from_bytes(&unsorted).unwrap_err(), CurveDecodeError::Unsorted(1));
+    }
+
+    #[test]
+    fn test_offset_time() {
+        let mut curve = three_point_curve();
+        curve.offset_time(2.5);
+        let times: Vec<f32> = curve.points.iter().map(|p| p.time).collect();
+        assert_eq!(times, vec![2.5, 3.5, 4.5]);
+        assert_eq!(curve.get_value(3.5), 1.0);
+    }
+
+    #[test]
+    fn test_append() {
+        let mut curve = three_point_curve();
+        let mut clip = FloatCurve::new();
+        clip.add_point_auto(10.0, 3.0);
+        clip.add_point_auto(11.0, 0.0);
+        curve.append(&clip, 0.5);
+
+        let times: Vec<f32> = curve.points.iter().map(|p| p.time).collect();
+        assert_eq!(times, vec![0.0, 1.0, 2.0, 2.5, 3.5]);
+        assert_eq!(curve.get_value(2.5), 3.0);
+        assert_eq!(curve.get_value(3.5), 0.0);
+
+        // no jumps across the joined region
+        let mut prev = curve.get_value(1.5);
+        for i in 1..=100 {
+            let value = curve.get_value(1.5 + i as f32 * 0.02);
+            assert!((value - prev).abs() < 0.1, "jump from {} to {} at sample {}", prev, value, i);
+            prev = value;
+        }
+
+        let mut empty = FloatCurve::new();
+        empty.append(&clip, 0.5);
+        assert_eq!(empty.points[0].time, 10.0);
+    }
+
+    #[test]
+    fn test_append_negative_gap() {
+        let mut curve = FloatCurve::new();
+        curve.add_point_auto(0.0, 0.0);
+        curve.add_point_auto(5.0, 1.0);
+        let mut clip = FloatCurve::new();
+        clip.add_point_auto(0.0, 2.0);
+        clip.add_point_auto(1.0, 3.0);
+        curve.append(&clip, -3.0);
+
+        let times: Vec<f32> = curve.iter().map(|p| p.time).collect();
+        assert_eq!(times, vec![0.0, 5.0, 5.0, 6.0]);
+        assert_eq!(curve.get_value(6.0), 3.0);
+        assert_eq!(FloatCurve::from_bytes(&curve.to_bytes()).unwrap().points().len(), 4);
+    }
+
+    #[test]
+    fn test_snap_points() {
+        let mut curve = FloatCurve::new();
+        curve.add_point_auto(0.1, 0.26);
+        curve.add_point_auto(0.9, 1.1);
+        curve.add_point_auto(1.2, -0.6);
+        curve.add_point_auto(2.3, 3.0);
+        curve.snap_points(0.5, 0.25);
+
+        // 0.9 and 1.2 both snap to 1.0, the earlier one is kept
+        let keys: Vec<(f32, f32)> = curve.points.iter().map(|p| (p.time, p.value)).collect();
+        assert_eq!(keys, vec![(0.0, 0.25), (1.0, 1.0), (2.5, 3.0)]);
+        assert_eq!(curve.points[1].arrive_tangent, (3.0 - 0.25) / 2.5);
+
+        // zero steps leave that axis alone
+        curve.snap_points(0.0, 2.0);
+        let keys: Vec<(f32, f32)> = curve.points.iter().map(|p| (p.time, p.value)).collect();
+        assert_eq!(keys, vec![(0.0, 0.0), (1.0, 2.0), (2.5, 4.0)]);
+    }
+
+    #[test]
+    fn test_snap_points_keeps_explicit_tangents() {
+        let mut curve = FloatCurve::new();
+        curve.add_point(0.0, 0.0, 0.0, 0.0);
+        curve.add_point(1.0, 1.0, 5.0, -5.0);
+        curve.add_point(2.0, 2.0, 0.0, 0.0);
+        curve.add_point(3.3, 3.0, 0.0, 0.0);
+        curve.snap_points(1.0, 1.0);
+        // only the last point moved, so its neighbor at 2.0 is refreshed but the point at 1.0 isn't
+        assert_eq!((curve[1].arrive_tangent, curve[1].leave_tangent), (5.0, -5.0));
+        assert_eq!(curve[2].arrive_tangent, (3.0 - 1.0) / 2.0);
+        assert_eq!(curve[3].time, 3.0);
+
+        // removing a merged point refreshes the point that lost its neighbor
+        let mut curve = FloatCurve::new();
+        curve.add_point(0.0, 0.0, 7.0, 7.0);
+        curve.add_point(1.0, 1.0, 7.0, 7.0);
+        curve.add_point(1.1, 4.0, 7.0, 7.0);
+        curve.add_point(2.0, 2.0, 7.0, 7.0);
+        curve.snap_points(1.0, 0.0);
+        let keys: Vec<(f32, f32)> = curve.iter().map(|p| (p.time, p.value)).collect();
+        assert_eq!(keys, vec![(0.0, 0.0), (1.0, 1.0), (2.0, 2.0)]);
+        assert_eq!(curve[0].leave_tangent, 7.0);
+        assert_eq!(curve[1].arrive_tangent, 1.0);
+        // the end point lost its neighbor too, so it went back to a flat auto tangent
+        assert_eq!(curve[2].arrive_tangent, 0.0);
+    }
+
+    #[test]
+    fn test_events_between() {
+        let mut curve = three_point_curve();
+        curve.add_event(1.0, "footstep");
+        curve.add_event(0.5, "muzzle flash");
+        curve.add_event(1.0, "sound");
+        assert_eq!(curve.events_between(0.0, 2.0), vec!["muzzle flash", "footstep", "sound"]);
+
+        // scrubbing in small steps reports each event exactly once
+        let mut fired = Vec::new();
+        let mut t = 0.0;
+        for _ in 0..37 {
+            let next = t + 0.07;
+            fired.extend(curve.events_between(t, next));
+            t = next;
+        }
+        assert_eq!(fired, vec!["muzzle flash", "footstep", "sound"]);
+
+        // range is exclusive of `from` and inclusive of `to`
+        assert_eq!(curve.events_between(0.5, 1.0), vec!["footstep", "sound"]);
+        assert!(curve.events_between(1.0, 0.0).is_empty());
+
+        curve.offset_time(1.0);
+        assert_eq!(curve.events_between(1.0, 1.5), vec!["muzzle flash"]);
+    }
+
+    #[test]
+    fn test_has_overshoot() {
+        // the middle key gets an upward auto tangent, so the curve rises above 1.0 before settling
+        let mut curve = FloatCurve::new();
+        curve.add_point_auto(0.0, 0.0);
+        curve.add_point_auto(1.0, 1.0);
+        curve.add_point_auto(2.0, 1.0);
+        assert!(curve.has_overshoot(16));
+        curve.set_point_tangent_mode(1, TangentMode::Flat);
+        assert!(!curve.has_overshoot(16));
+
+        let mut monotonic = FloatCurve::new();
+        monotonic.add_point_auto(0.0, 0.0);
+        monotonic.add_point_auto(1.0, 1.0);
+        monotonic.add_point_auto(2.0, 2.0);
+        assert!(!monotonic.has_overshoot(16));
+        assert!(!FloatCurve::new().has_overshoot(16));
+    }
+
+    #[test]
+    fn test_calc_monotone_tangents() {
+        let mut curve = FloatCurve::new();
+        for (time, value) in [(0.0, 0.0), (1.0, 0.1), (2.0, 5.0), (3.0, 5.1), (5.0, 5.2), (5.5, 9.0)] {
+            curve.add_point_auto(time, value);
+        }
+        assert!(curve.has_overshoot(64));
+
+        curve.calc_monotone_tangents();
+        assert!(!curve.has_overshoot(64));
+        let mut last = curve.get_value(0.0);
+        for i in 1..=1000 {
+            let value = curve.get_value(5.5 * i as f32 / 1000.0);
+            assert!(value >= last - 0.000_01, "curve decreased at sample {}", i);
+            last = value;
+        }
+        assert!(curve.points.iter().all(|p| p.mode == TangentMode::Broken));
+
+        // tangents survive later recalculation
+        let tangent = curve.points[2].leave_tangent;
+        curve.recalculate_tangents();
+        assert_eq!(curve.points[2].leave_tangent, tangent);
+
+        // plateaus stay flat
+        let mut plateau = FloatCurve::new();
+        for (time, value) in [(0.0, 0.0), (1.0, 1.0), (2.0, 1.0), (3.0, 2.0)] {
+            plateau.add_point_auto(time, value);
+        }
+        plateau.calc_monotone_tangents();
+        assert_eq!(plateau.get_value(1.5), 1.0);
+    }
+
+    #[test]
+    fn test_points_index_and_iter() {
+        let mut curve = FloatCurve::new();
+        for (time, value) in [(2.0, 1.0), (0.0, 0.0), (3.5, -1.0), (1.0, 4.0)] {
+            curve.add_point_auto(time, value);
+        }
+        assert_eq!(curve.points().len(), 4);
+        let times: Vec<f32> = curve.iter().map(|p| p.time).collect();
+        assert_eq!(times, vec![0.0, 1.0, 2.0, 3.5]);
+        assert!(curve.iter().zip(curve.iter().skip(1)).all(|(a, b)| a.time <= b.time));
+        assert_eq!(curve[1].value, 4.0);
+        assert_eq!(curve[3].time, curve.points()[3].time);
+    }
+
+    #[test]
+    fn test_set_point_value() {
+        let mut curve = FloatCurve::new();
+        curve.add_point(0.0, 0.0, 0.0, 0.0);
+        curve.add_point(1.0, 10.0, 0.0, 0.0);
+        curve.set_point_value(1, 5.0);
+        assert_eq!(curve.get_value(1.0), 5.0);
+
+        // neighbors' auto and linear tangents follow the new value
+        let mut curve = FloatCurve::new();
+        for (time, value) in [(0.0, 0.0), (1.0, 1.0), (2.0, 4.0), (3.0, 9.0)] {
+            curve.add_point_auto(time, value);
+        }
+        curve.set_point_tangent_mode(3, TangentMode::Linear);
+        curve.set_point_value(2, -4.0);
+        let mut expected = FloatCurve::new();
+        for (time, value) in [(0.0, 0.0), (1.0, 1.0), (2.0, -4.0), (3.0, 9.0)] {
+            expected.add_point_auto(time, value);
+        }
+        expected.set_point_tangent_mode(3, TangentMode::Linear);
+        for t in [1.25, 1.5, 1.75, 2.5] {
+            assert_eq!(curve.get_value(t), expected.get_value(t), "at {}", t);
+        }
+    }
+}
@@ -1,8 +1,20 @@
-//! A simple structure for keeping track of mouse position/delta during drag operations.
+//! Simple structures for keeping track of mouse position/delta during drag operations, and for
+//! telling clicks and double-clicks apart from drags.
 
-use cgmath::{Point2, Vector2, Zero};
+use cgmath::{MetricSpace, Point2, Vector2, Zero};
 
 
+/// The current phase of a `DragState`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DragPhase {
+    /// No drag is active.
+    Idle,
+    /// A drag has been activated but hasn't been updated with a new position yet.
+    Armed,
+    /// A drag is active and has received at least one position update.
+    Dragging,
+}
+
 /// A simple structure for keeping track of mouse position/delta during drag operations.
 #[derive(Debug, Clone)]
 pub struct DragState<T> {
@@ -10,6 +22,12 @@ pub struct DragState<T> {
     state: Option<T>,
     /// Last known position of the mouse
     prev_pos: Option<Point2<f32>>,
+    /// True once the active drag has received a position update
+    dragging: bool,
+    /// Recent positions passed to `update`, oldest first
+    history: Vec<Point2<f32>>,
+    /// Maximum number of positions kept in `history`, 0 if recording is disabled
+    history_capacity: usize,
 }
 
 impl<T> Default for DragState<T> {
@@ -20,32 +38,81 @@ impl<T> DragState<T> {
     pub const fn new() -> Self {
         DragState {
             state: None,
-            prev_pos: None
+            prev_pos: None,
+            dragging: false,
+            history: Vec::new(),
+            history_capacity: 0,
+        }
+    }
+
+    /// Creates a new `DragState` that records up to `capacity` of the most recent positions
+    /// passed to `update` during a drag. See `path`.
+    pub fn with_history(capacity: usize) -> Self {
+        DragState {
+            history: Vec::with_capacity(capacity),
+            history_capacity: capacity,
+            ..DragState::new()
         }
     }
 
+    /// Returns the recorded positions of the current (or most recent) drag, oldest first.
+    /// Always empty unless created with `with_history`.
+    pub fn path(&self) -> &[Point2<f32>] { &self.history }
+
     /// True if the drag is currently active i.e. holding down the button
     pub fn active(&self) -> bool { self.state.is_some() }
 
+    /// Returns the current phase of the drag
+    pub fn phase(&self) -> DragPhase {
+        if !self.active() { DragPhase::Idle }
+        else if self.dragging { DragPhase::Dragging }
+        else { DragPhase::Armed }
+    }
+
     /// Reterns the user state data if there is any
     pub fn state(&self) -> &Option<T> { &self.state }
 
     /// Begins a drag operation with the provided state. Returns the previous state, if any.
     pub fn activate(&mut self, new_state: T, starting_pos: Option<impl Into<Point2<f32>>>) -> Option<T> {
         self.prev_pos = starting_pos.map(|i| i.into());
+        self.dragging = false;
+        self.history.clear();
         self.state.replace(new_state)
     }
 
     /// Ends the current drag operation if active and returns the state.
     pub fn deactivate(&mut self) -> Option<T> {
         self.prev_pos = None;
+        self.dragging = false;
         self.state.take()
     }
 
+    /// Cancels the current drag operation if active, discarding the state.
+    pub fn cancel(&mut self) {
+        self.prev_pos = None;
+        self.dragging = false;
+        self.state = None;
+    }
+
     /// Returns Err(()) if not active, otherwise returns Ok(∆position)
     pub fn update(&mut self, new_pos: impl Into<Point2<f32>>) -> Result<Vector2<f32>, ()> {
-        let new_pos = new_pos.into();
+        self.update_with(new_pos, |p| p)
+    }
+
+    /// Like `update`, but passes the new position through `transform` first, e.g. to snap it to a
+    /// grid or clamp it to a region. The delta, stored position, and path history all use the
+    /// transformed position.
+    #[allow(clippy::result_unit_err)]
+    pub fn update_with<F: Fn(Point2<f32>) -> Point2<f32>>(&mut self, new_pos: impl Into<Point2<f32>>, transform: F) -> Result<Vector2<f32>, ()> {
+        let new_pos = transform(new_pos.into());
         if self.active() {
+            self.dragging = true;
+            if self.history_capacity > 0 {
+                if self.history.len() == self.history_capacity {
+                    self.history.remove(0);
+                }
+                self.history.push(new_pos);
+            }
             match self.prev_pos {
                 Some(prev) => {
                     let delta = new_pos - prev;
@@ -61,3 +128,188 @@ impl<T> DragState<T> {
         else { Err(()) }
     }
 }
+
+
+/// What a press and release of a pointer button amounted to. See `PointerState`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PointerEvent {
+    /// A quick press and release without much movement.
+    Click,
+    /// A click shortly after, and close to, a previous click.
+    DoubleClick,
+    /// A press that moved too far or was held too long to count as a click.
+    DragEnd,
+}
+
+/// Tells clicks, double-clicks, and drags apart from pointer button presses and releases.
+/// Times are in seconds, and distances are in the same units as the positions.
+#[derive(Debug, Clone)]
+pub struct PointerState {
+    /// Furthest the pointer can move between press and release for it to still count as a click.
+    /// Also the furthest apart two clicks can be to make a double-click.
+    pub click_max_distance: f32,
+    /// Longest the button can be held for it to still count as a click.
+    pub click_max_duration: f32,
+    /// Longest time from one click's release to the next click's release for a double-click.
+    pub double_click_interval: f32,
+    /// Position and time of the current press, if the button is down
+    down: Option<(Point2<f32>, f32)>,
+    /// Position and time of the last click that could still become a double-click
+    last_click: Option<(Point2<f32>, f32)>,
+}
+
+impl Default for PointerState {
+    /// Uses a 4 unit distance threshold, 0.3s click duration, and 0.4s double-click interval.
+    fn default() -> Self { PointerState::new(4.0, 0.3, 0.4) }
+}
+
+impl PointerState {
+    pub const fn new(click_max_distance: f32, click_max_duration: f32, double_click_interval: f32) -> Self {
+        PointerState { click_max_distance, click_max_duration, double_click_interval, down: None, last_click: None }
+    }
+
+    /// True if the button is currently held down.
+    pub fn is_down(&self) -> bool { self.down.is_some() }
+
+    /// Records the button being pressed at `pos` at time `now`.
+    pub fn register_down(&mut self, pos: impl Into<Point2<f32>>, now: f32) {
+        self.down = Some((pos.into(), now));
+    }
+
+    /// Records the button being released at `pos` at time `now`, and returns what kind of
+    /// interaction that completed. A release without a matching press is reported as `DragEnd`.
+    /// After a `DoubleClick`, the next click starts a new sequence rather than making another one.
+    pub fn register_up(&mut self, pos: impl Into<Point2<f32>>, now: f32) -> PointerEvent {
+        let pos = pos.into();
+        let Some((down_pos, down_time)) = self.down.take() else {
+            self.last_click = None;
+            return PointerEvent::DragEnd;
+        };
+        if down_pos.distance(pos) > self.click_max_distance || now - down_time > self.click_max_duration {
+            self.last_click = None;
+            return PointerEvent::DragEnd;
+        }
+
+        match self.last_click.take() {
+            Some((last_pos, last_time)) if now - last_time <= self.double_click_interval
+                && last_pos.distance(pos) <= self.click_max_distance => PointerEvent::DoubleClick,
+            _ => {
+                self.last_click = Some((pos, now));
+                PointerEvent::Click
+            }
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::{DragPhase, DragState, PointerEvent, PointerState};
+    use cgmath::{Point2, Vector2};
+
+    #[test]
+    fn test_drag_phases() {
+        let mut drag = DragState::new();
+        assert_eq!(drag.phase(), DragPhase::Idle);
+        drag.activate(1, Some([0.0, 0.0]));
+        assert_eq!(drag.phase(), DragPhase::Armed);
+        assert_eq!(drag.update([1.0, 2.0]), Ok(Vector2::new(1.0, 2.0)));
+        assert_eq!(drag.phase(), DragPhase::Dragging);
+        assert_eq!(drag.deactivate(), Some(1));
+        assert_eq!(drag.phase(), DragPhase::Idle);
+    }
+
+    #[test]
+    fn test_drag_cancel() {
+        let mut drag = DragState::new();
+        drag.activate(1, Some([0.0, 0.0]));
+        drag.update([1.0, 1.0]).unwrap();
+        drag.cancel();
+        assert_eq!(drag.phase(), DragPhase::Idle);
+        assert_eq!(drag.state(), &None);
+        assert!(drag.update([2.0, 2.0]).is_err());
+        assert_eq!(drag.deactivate(), None);
+    }
+
+    #[test]
+    fn test_drag_update_with() {
+        let snap = |p: Point2<f32>| Point2::new(p.x.round(), p.y.round());
+        let mut drag = DragState::with_history(4);
+        drag.activate((), Some([0.0, 0.0]));
+        assert_eq!(drag.update_with([0.4, 0.6], snap), Ok(Vector2::new(0.0, 1.0)));
+        assert_eq!(drag.update_with([0.2, 1.3], snap), Ok(Vector2::new(0.0, 0.0)));
+        assert_eq!(drag.update_with([2.7, 0.9], snap), Ok(Vector2::new(3.0, 0.0)));
+        // unsnapped updates continue from the snapped position
+        assert_eq!(drag.update([3.5, 1.0]), Ok(Vector2::new(0.5, 0.0)));
+        assert_eq!(drag.path(), &[Point2::new(0.0, 1.0), Point2::new(0.0, 1.0), Point2::new(3.0, 1.0), Point2::new(3.5, 1.0)]);
+
+        drag.deactivate();
+        assert!(drag.update_with([1.0, 1.0], snap).is_err());
+    }
+
+    #[test]
+    fn test_drag_history() {
+        let mut drag = DragState::with_history(3);
+        drag.activate((), Some([0.0, 0.0]));
+        drag.update([1.0, 0.0]).unwrap();
+        drag.update([2.0, 0.0]).unwrap();
+        assert_eq!(drag.path(), &[Point2::new(1.0, 0.0), Point2::new(2.0, 0.0)]);
+        drag.update([3.0, 0.0]).unwrap();
+        drag.update([4.0, 1.0]).unwrap();
+        assert_eq!(drag.path(), &[Point2::new(2.0, 0.0), Point2::new(3.0, 0.0), Point2::new(4.0, 1.0)]);
+
+        // path survives the end of the drag but is reset by the next one
+        drag.deactivate();
+        assert_eq!(drag.path().len(), 3);
+        drag.activate((), None::<[f32; 2]>);
+        assert!(drag.path().is_empty());
+
+        let mut no_history = DragState::new();
+        no_history.activate((), None::<[f32; 2]>);
+        no_history.update([1.0, 1.0]).unwrap();
+        assert!(no_history.path().is_empty());
+    }
+
+    #[test]
+    fn test_pointer_click() {
+        let mut pointer = PointerState::default();
+        pointer.register_down([10.0, 10.0], 1.0);
+        assert!(pointer.is_down());
+        assert_eq!(pointer.register_up([11.0, 10.0], 1.1), PointerEvent::Click);
+        assert!(!pointer.is_down());
+        // too late to be a double-click
+        pointer.register_down([10.0, 10.0], 3.0);
+        assert_eq!(pointer.register_up([10.0, 10.0], 3.1), PointerEvent::Click);
+    }
+
+    #[test]
+    fn test_pointer_double_click() {
+        let mut pointer = PointerState::default();
+        pointer.register_down([10.0, 10.0], 1.0);
+        assert_eq!(pointer.register_up([10.0, 10.0], 1.05), PointerEvent::Click);
+        pointer.register_down([11.0, 9.0], 1.2);
+        assert_eq!(pointer.register_up([11.0, 9.0], 1.25), PointerEvent::DoubleClick);
+        // a third quick tap starts over
+        pointer.register_down([11.0, 9.0], 1.35);
+        assert_eq!(pointer.register_up([11.0, 9.0], 1.4), PointerEvent::Click);
+
+        // quick taps too far apart aren't a double-click
+        pointer.register_down([100.0, 100.0], 1.5);
+        assert_eq!(pointer.register_up([100.0, 100.0], 1.55), PointerEvent::Click);
+    }
+
+    #[test]
+    fn test_pointer_drag_end() {
+        let mut pointer = PointerState::default();
+        pointer.register_down([0.0, 0.0], 1.0);
+        assert_eq!(pointer.register_up([30.0, 5.0], 1.1), PointerEvent::DragEnd);
+        // held too long without moving
+        pointer.register_down([0.0, 0.0], 2.0);
+        assert_eq!(pointer.register_up([0.0, 0.0], 3.0), PointerEvent::DragEnd);
+        // a drag doesn't count as the first half of a double-click
+        pointer.register_down([0.0, 0.0], 3.1);
+        assert_eq!(pointer.register_up([0.0, 0.0], 3.15), PointerEvent::Click);
+        // release without a press
+        assert_eq!(pointer.register_up([0.0, 0.0], 3.2), PointerEvent::DragEnd);
+    }
+}
@@ -0,0 +1,112 @@
+//! A dense 2D grid container, addressable by cell or by `Rect` region.
+
+use crate::rect::Rect;
+
+
+/// A dense, row-major 2D grid of `T` values with fixed dimensions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Grid2D<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid2D<T> {
+    /// Creates a `width` by `height` grid with every cell set to `default`.
+    pub fn new(width: usize, height: usize, default: T) -> Self {
+        Grid2D { width, height, cells: vec![default; width * height] }
+    }
+
+    /// Sets every cell inside `rect` to `value`. The parts of `rect` that fall outside the grid
+    /// are ignored.
+    pub fn fill_rect(&mut self, rect: Rect<usize>, value: T) {
+        let (x0, y0, x1, y1) = self.clip(rect);
+        for y in y0..y1 {
+            let row = y * self.width;
+            self.cells[row + x0..row + x1].fill(value.clone());
+        }
+    }
+}
+
+impl<T> Grid2D<T> {
+    pub fn width(&self) -> usize { self.width }
+    pub fn height(&self) -> usize { self.height }
+
+    /// Returns the grid's bounds as a `Rect` at the origin.
+    pub fn bounds(&self) -> Rect<usize> {
+        Rect { x: 0, y: 0, w: self.width, h: self.height }
+    }
+
+    /// Returns a reference to the cell at (`x`, `y`), or `None` if it's outside the grid.
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        self.index_of(x, y).map(|i| &self.cells[i])
+    }
+
+    /// Returns a mutable reference to the cell at (`x`, `y`), or `None` if it's outside the grid.
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut T> {
+        self.index_of(x, y).map(|i| &mut self.cells[i])
+    }
+
+    /// Iterates over the cells inside `rect` in row-major order, yielding `(x, y, &cell)`. The
+    /// parts of `rect` that fall outside the grid are skipped.
+    pub fn iter_rect(&self, rect: Rect<usize>) -> impl Iterator<Item = (usize, usize, &T)> + '_ {
+        let (x0, y0, x1, y1) = self.clip(rect);
+        (y0..y1).flat_map(move |y| (x0..x1).map(move |x| (x, y, &self.cells[y * self.width + x])))
+    }
+
+    fn index_of(&self, x: usize, y: usize) -> Option<usize> {
+        (x < self.width && y < self.height).then(|| y * self.width + x)
+    }
+
+    // clips `rect` to the grid, returning the half-open cell range (x0, y0, x1, y1)
+    fn clip(&self, rect: Rect<usize>) -> (usize, usize, usize, usize) {
+        let x0 = rect.x.min(self.width);
+        let y0 = rect.y.min(self.height);
+        let x1 = rect.x.saturating_add(rect.w).min(self.width);
+        let y1 = rect.y.saturating_add(rect.h).min(self.height);
+        (x0, y0, x1, y1)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fill_rect() {
+        let mut grid = Grid2D::new(8, 6, 0);
+        grid.fill_rect(Rect { x: 2, y: 1, w: 3, h: 2 }, 7);
+        for y in 0..6 {
+            for x in 0..8 {
+                let inside = (2..5).contains(&x) && (1..3).contains(&y);
+                assert_eq!(*grid.get(x, y).unwrap(), if inside { 7 } else { 0 }, "({}, {})", x, y);
+            }
+        }
+        assert_eq!(grid.get(8, 0), None);
+        assert_eq!(grid.get(0, 6), None);
+
+        // partially out of bounds is clipped
+        grid.fill_rect(Rect { x: 6, y: 4, w: 10, h: 10 }, 3);
+        assert_eq!(grid.iter_rect(grid.bounds()).filter(|(_, _, v)| **v == 3).count(), 4);
+        grid.fill_rect(Rect { x: 20, y: 20, w: 2, h: 2 }, 9);
+        assert!(grid.iter_rect(grid.bounds()).all(|(_, _, v)| *v != 9));
+
+        *grid.get_mut(0, 0).unwrap() = 1;
+        assert_eq!(grid.get(0, 0), Some(&1));
+        assert!(grid.get_mut(100, 0).is_none());
+    }
+
+    #[test]
+    fn test_iter_rect() {
+        let mut grid = Grid2D::new(4, 4, 0);
+        for y in 0..4 {
+            for x in 0..4 {
+                *grid.get_mut(x, y).unwrap() = y * 4 + x;
+            }
+        }
+        let cells: Vec<_> = grid.iter_rect(Rect { x: 1, y: 2, w: 2, h: 5 }).collect();
+        assert_eq!(cells, vec![(1, 2, &9), (2, 2, &10), (1, 3, &13), (2, 3, &14)]);
+        assert_eq!(grid.iter_rect(Rect { x: 4, y: 0, w: 1, h: 1 }).count(), 0);
+    }
+}
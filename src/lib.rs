@@ -1,8 +1,10 @@
 use std::cell::UnsafeCell;
 pub use cgmath;
 
-use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
-use cgmath::{Vector3, Matrix4, Deg, Point3, dot, EuclideanSpace, Transform as CgTransform};
+use std::fmt::{Debug, Display, Formatter};
+use std::ops::Index;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, AtomicUsize, Ordering};
+use cgmath::{Vector3, Matrix4, Deg, Point3, dot, EuclideanSpace, InnerSpace, Transform as CgTransform};
 use num::traits::real::Real;
 
 pub mod aabb;
@@ -13,7 +15,7 @@ pub use color::{Color, ColorSpace};
 pub mod noise;
 
 pub mod transform;
-pub use transform::Transform;
+pub use transform::{CachedTransform, Transform, Transform2D};
 
 pub mod paths;
 
@@ -22,6 +24,9 @@ pub mod curve;
 pub mod rect;
 pub use rect::Rect;
 
+pub mod grid;
+pub use grid::Grid2D;
+
 pub mod once;
 
 pub mod drag;
@@ -29,6 +34,11 @@ pub mod drag;
 pub mod cell;
 pub use cell::SimpleCell;
 
+pub mod time;
+
+#[cfg(feature = "test-util")]
+pub mod test_util;
+
 
 #[derive(Copy, Clone, Debug)]
 pub struct Plane {
@@ -36,6 +46,39 @@ pub struct Plane {
     d: f32
 }
 
+impl Plane {
+    /// Constructs a plane containing all points `p` where `dot(n, p) == d`.
+    pub fn new(n: Vector3<f32>, d: f32) -> Self { Plane { n, d } }
+
+    /// Returns the closest point on the plane to `p`.
+    /// The normal doesn't need to be normalized.
+    pub fn project_point(&self, p: Point3<f32>) -> Point3<f32> {
+        let (unit_n, dist) = self.unit_normal_and_distance(p);
+        p - unit_n * dist
+    }
+
+    /// Mirrors a point across the plane.
+    /// The normal doesn't need to be normalized.
+    pub fn reflect_point(&self, p: Point3<f32>) -> Point3<f32> {
+        let (unit_n, dist) = self.unit_normal_and_distance(p);
+        p - unit_n * (2.0 * dist)
+    }
+
+    /// Mirrors a direction vector across the plane, e.g. to bounce a velocity off a surface.
+    /// The normal doesn't need to be normalized.
+    pub fn reflect_vector(&self, v: Vector3<f32>) -> Vector3<f32> {
+        let unit_n = self.n.normalize();
+        v - unit_n * (2.0 * dot(v, unit_n))
+    }
+
+    // returns the normalized normal, and the signed distance from the plane to `p` along it
+    fn unit_normal_and_distance(&self, p: Point3<f32>) -> (Vector3<f32>, f32) {
+        let len = self.n.magnitude();
+        let unit_n = self.n / len;
+        (unit_n, dot(unit_n, p.to_vec()) - self.d / len)
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct FrustumPlanes {
     pub left: Plane,
@@ -46,6 +89,71 @@ pub struct FrustumPlanes {
     pub rear: Plane,
 }
 
+/// Result of testing a shape against a volume.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Containment {
+    /// The shape is entirely inside the volume.
+    Inside,
+    /// The shape is partially inside the volume.
+    Intersecting,
+    /// The shape is entirely outside the volume.
+    Outside,
+}
+
+impl FrustumPlanes {
+    /// Returns all six planes in the order left, right, bottom, top, front, rear.
+    pub fn planes(&self) -> [Plane; 6] {
+        [self.left, self.right, self.bottom, self.top, self.front, self.rear]
+    }
+
+    /// Classifies a sphere as inside, outside, or intersecting the frustum. A point is considered
+    /// inside a plane when `dot(n, p) - d <= 0`, and plane normals are assumed to be normalized.
+    pub fn classify_sphere(&self, center: Point3<f32>, radius: f32) -> Containment {
+        let mut result = Containment::Inside;
+        for plane in self {
+            let dist = dot(plane.n, center.to_vec()) - plane.d;
+            if dist > radius {
+                return Containment::Outside;
+            }
+            if dist > -radius {
+                result = Containment::Intersecting;
+            }
+        }
+        result
+    }
+}
+
+impl Index<usize> for FrustumPlanes {
+    type Output = Plane;
+
+    /// Indexes the planes in the same order as `planes()`.
+    fn index(&self, index: usize) -> &Self::Output {
+        match index {
+            0 => &self.left,
+            1 => &self.right,
+            2 => &self.bottom,
+            3 => &self.top,
+            4 => &self.front,
+            5 => &self.rear,
+            _ => panic!("Index {} out of bounds for FrustumPlanes", index)
+        }
+    }
+}
+
+impl IntoIterator for FrustumPlanes {
+    type Item = Plane;
+    type IntoIter = std::array::IntoIter<Plane, 6>;
+
+    fn into_iter(self) -> Self::IntoIter { self.planes().into_iter() }
+}
+
+impl IntoIterator for &FrustumPlanes {
+    type Item = Plane;
+    type IntoIter = std::array::IntoIter<Plane, 6>;
+
+    fn into_iter(self) -> Self::IntoIter { self.planes().into_iter() }
+}
+
 
 pub fn view_to_frustum(pitch: f32, yaw: f32, fov: Deg<f32>, aspect: f32, near_z: f32, far_z: f32) -> FrustumPlanes {
     let forward = Vector3::new(0.0, 0.0, 1.0);
@@ -75,6 +183,15 @@ pub fn lerp(a: f32, b: f32, alpha: f32) -> f32 {
     (a * (1.0 - alpha)) + (b * alpha)
 }
 
+/// Interpolates between two angles in degrees, going the short way around the circle (e.g. from
+/// 350° to 10° passes through 0° rather than 180°). The result is wrapped into `[0, 360)`.
+pub fn lerp_angle(a: f32, b: f32, alpha: f32) -> f32 {
+    let diff = (b - a + 180.0).rem_euclid(360.0) - 180.0;
+    let result = (a + diff * alpha).rem_euclid(360.0);
+    // rem_euclid can round up to exactly 360 for tiny negative values
+    if result >= 360.0 { 0.0 } else { result }
+}
+
 pub fn aabb_plane_intersection(bmin: Point3<f32>, bmax: Point3<f32>, plane: Plane) -> bool {
     // Convert AABB to center-extents representation
     let center = (bmax + bmin.to_vec()) * 0.5; // Compute AABB center
@@ -91,13 +208,9 @@ pub fn aabb_plane_intersection(bmin: Point3<f32>, bmax: Point3<f32>, plane: Plan
 }
 
 pub fn aabb_frustum_intersection(bmin: Point3<f32>, bmax: Point3<f32>, p: FrustumPlanes) -> bool {
+    let aabb = aabb::AABB::from(bmin, bmax);
     for plane in &[p.left, p.right, p.top, p.bottom] {
-        let mut closest_pt = Vector3::new(0.0, 0.0, 0.0);
-
-        closest_pt.x = if plane.n.x > 0.0 { bmin.x } else { bmax.x };
-        closest_pt.y = if plane.n.y > 0.0 { bmin.y } else { bmax.y };
-        closest_pt.z = if plane.n.z > 0.0 { bmin.z } else { bmax.z };
-
+        let closest_pt = aabb.closest_corner_to_plane(plane.n).to_vec();
         if dot(plane.n, closest_pt) > 0.0 {
             return false;
         }
@@ -105,10 +218,31 @@ pub fn aabb_frustum_intersection(bmin: Point3<f32>, bmax: Point3<f32>, p: Frustu
     true
 }
 
+/// Computes the single point where three planes intersect.
+/// Returns `None` if any two of the planes are parallel (or the three otherwise don't meet at a point).
+pub fn intersect_three_planes(a: Plane, b: Plane, c: Plane) -> Option<Point3<f32>> {
+    let bc = b.n.cross(c.n);
+    let denom = dot(a.n, bc);
+    if denom.abs() < f32::EPSILON { return None }
+    let ca = c.n.cross(a.n);
+    let ab = a.n.cross(b.n);
+    Some(Point3::from_vec((bc * a.d + ca * b.d + ab * c.d) / denom))
+}
+
 pub fn point_box_intersection(point: [f32; 2], box_mins: [f32; 2], box_maxes: [f32; 2]) -> bool {
     point[0] >= box_mins[0] && point[0] <= box_maxes[0] && point[1] >= box_mins[1] && point[1] <= box_maxes[1]
 }
 
+/// Batch version of `point_box_intersection`, testing many points against the same box.
+/// `out` is cleared and filled with one result per point. Reusing the same `out` between calls
+/// avoids reallocating.
+pub fn points_in_box(points: &[[f32; 2]], box_mins: [f32; 2], box_maxes: [f32; 2], out: &mut Vec<bool>) {
+    out.clear();
+    out.extend(points.iter().map(|p| {
+        (p[0] >= box_mins[0]) & (p[0] <= box_maxes[0]) & (p[1] >= box_mins[1]) & (p[1] <= box_maxes[1])
+    }));
+}
+
 pub fn format_bytes(bytes: u32, digits: u32) -> String {
     if bytes < 1024 {
         let s = bytes.to_string();
@@ -167,6 +301,28 @@ pub fn normalize_with_constant(constant: f32, mut a: f32, mut b: f32) -> (f32, f
     (constant, a, b)
 }
 
+/// Normalizes a set of weights in-place so that they sum to 1.
+/// If the weights sum to (nearly) zero, they are all set to the same value instead.
+pub fn normalize_weights(weights: &mut [f32]) {
+    if weights.is_empty() { return }
+    let sum: f32 = weights.iter().sum();
+    if sum.abs() < f32::EPSILON {
+        let uniform = 1.0 / weights.len() as f32;
+        for w in weights.iter_mut() { *w = uniform; }
+    }
+    else {
+        for w in weights.iter_mut() { *w /= sum; }
+    }
+}
+
+/// Returns a copy of the given weights normalized so that they sum to 1.
+/// See `normalize_weights`.
+pub fn normalized_weights(weights: &[f32]) -> Vec<f32> {
+    let mut result = weights.to_vec();
+    normalize_weights(&mut result);
+    result
+}
+
 pub fn slice_max<T: Real>(slice: &[T]) -> T {
     if slice.len() == 0 { panic!("Can't get the maximum of an empty slice!") }
     let mut max = slice[0];
@@ -185,6 +341,16 @@ pub fn slice_min<T: Real>(slice: &[T]) -> T {
     min
 }
 
+/// Like `slice_max`, but for `Ord` types such as integers. Returns `None` if the slice is empty.
+pub fn slice_max_ord<T: Ord + Copy>(slice: &[T]) -> Option<T> {
+    slice.iter().copied().max()
+}
+
+/// Like `slice_min`, but for `Ord` types such as integers. Returns `None` if the slice is empty.
+pub fn slice_min_ord<T: Ord + Copy>(slice: &[T]) -> Option<T> {
+    slice.iter().copied().min()
+}
+
 pub fn array_max<const N: usize, T: Real>(array: [T; N]) -> T {
     if N == 0 { panic!("Can't get the maximum of an empty array!") }
     let mut max = array[0];
@@ -203,14 +369,61 @@ pub fn array_min<const N: usize, T: Real>(array: [T; N]) -> T {
     min
 }
 
+pub fn array_sum<const N: usize, T: Real>(array: [T; N]) -> T {
+    let mut sum = T::zero();
+    for value in array {
+        sum = sum + value;
+    }
+    sum
+}
+
+pub fn array_mean<const N: usize, T: Real>(array: [T; N]) -> T {
+    if N == 0 { panic!("Can't get the mean of an empty array!") }
+    array_sum(array) / T::from(N).unwrap()
+}
+
 
-/// Thread-safe monotonically-incrementing counter
+/// Thread-safe monotonically-incrementing counter.
+/// After reaching `u64::MAX` the counter wraps back around to zero.
 pub struct MonoCounter(AtomicU64);
 impl MonoCounter {
     pub const fn new() -> Self { MonoCounter(AtomicU64::new(0)) }
     pub fn next(&self) -> u64 {
         self.0.fetch_add(1, Ordering::SeqCst)
     }
+    /// Returns the value the next call to `next` would return, without incrementing.
+    /// This is a racy snapshot: other threads may increment the counter at any time.
+    pub fn current(&self) -> u64 {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+impl Default for MonoCounter {
+    fn default() -> Self { MonoCounter::new() }
+}
+/// Shows a racy snapshot of the current count (see `MonoCounter::current`).
+impl Debug for MonoCounter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("MonoCounter").field(&self.current()).finish()
+    }
+}
+/// Shows a racy snapshot of the current count (see `MonoCounter::current`).
+impl Display for MonoCounter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.current())
+    }
+}
+
+/// Thread-safe monotonically-incrementing counter backed by a 32-bit integer, for smaller ID spaces.
+/// After reaching `u32::MAX` the counter wraps back around to zero.
+pub struct MonoCounter32(AtomicU32);
+impl MonoCounter32 {
+    pub const fn new() -> Self { MonoCounter32(AtomicU32::new(0)) }
+    pub fn next(&self) -> u32 {
+        self.0.fetch_add(1, Ordering::SeqCst)
+    }
+}
+impl Default for MonoCounter32 {
+    fn default() -> Self { MonoCounter32::new() }
 }
 
 
@@ -236,6 +449,8 @@ impl MonoCounter {
 pub struct Defer<S> {
     state: UnsafeCell<Option<S>>,
     locked: AtomicBool,
+    // mirrors `state.is_some()` so it can be inspected without touching the UnsafeCell
+    deferred: AtomicBool,
 }
 
 impl<S> Defer<S> {
@@ -243,7 +458,8 @@ impl<S> Defer<S> {
     pub const fn new() -> Self {
         Defer {
             state: UnsafeCell::new(None),
-            locked: AtomicBool::new(false)
+            locked: AtomicBool::new(false),
+            deferred: AtomicBool::new(false),
         }
     }
 
@@ -258,6 +474,7 @@ impl<S> Defer<S> {
         let was_locked = self.locked.fetch_or(true, Ordering::SeqCst);
         if was_locked { panic!("Defer::<{}>::defer() called while lock was already held", std::any::type_name::<S>()); }
         unsafe { self.state.get().write(Some(state)); }
+        self.deferred.store(true, Ordering::SeqCst);
         self.locked.store(false, Ordering::SeqCst);
     }
 
@@ -273,6 +490,7 @@ impl<S> Defer<S> {
         else {
             // wasn't locked but is now
             unsafe { self.state.get().write(Some(state)); }
+            self.deferred.store(true, Ordering::SeqCst);
             self.locked.store(false, Ordering::SeqCst);
             true
         }
@@ -287,6 +505,7 @@ impl<S> Defer<S> {
         if was_locked { panic!("Defer::<{}>::execute() called while lock was already held", std::any::type_name::<S>()); }
         let did_run = unsafe {
             let opt = (&mut *self.state.get()).take();
+            self.deferred.store(false, Ordering::SeqCst);
             match opt {
                 Some(value) => {
                     f(value); true
@@ -313,6 +532,7 @@ impl<S> Defer<S> {
             // wasn't locked but is now
             let did_run = unsafe {
                 let opt = (&mut *self.state.get()).take();
+                self.deferred.store(false, Ordering::SeqCst);
                 match opt {
                     Some(value) => {
                         f(value); true
@@ -324,4 +544,445 @@ impl<S> Defer<S> {
             Ok(did_run)
         }
     }
+
+    /// Consumes this `Defer` and returns a new one holding the result of `f` applied to the
+    /// deferred state, if one was stored. `f` isn't called if nothing was deferred.
+    pub fn map_deferred<R>(self, f: impl FnOnce(S) -> R) -> Defer<R> {
+        let mapped = Defer::new();
+        if let Some(state) = self.state.into_inner() {
+            mapped.defer(f(state));
+        }
+        mapped
+    }
+}
+
+impl<S> Debug for Defer<S> {
+    /// Only inspects the atomic flags, so this is safe to call even while another thread holds the lock.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Defer")
+            .field("deferred", &self.deferred.load(Ordering::SeqCst))
+            .field("locked", &self.locked.load(Ordering::SeqCst))
+            .finish()
+    }
+}
+
+
+/// Like `Defer`, but stores any number of deferred states instead of just one. `execute` runs the
+/// closure for each pending state in the order they were deferred.
+pub struct DeferQueue<S> {
+    queue: UnsafeCell<Vec<S>>,
+    locked: AtomicBool,
+    // mirrors `queue.len()` so it can be inspected without touching the UnsafeCell
+    len: AtomicUsize,
+}
+
+impl<S> DeferQueue<S> {
+    /// Constructs a new, empty `DeferQueue`.
+    pub const fn new() -> Self {
+        DeferQueue {
+            queue: UnsafeCell::new(Vec::new()),
+            locked: AtomicBool::new(false),
+            len: AtomicUsize::new(0),
+        }
+    }
+
+    /// Constructs a new, empty `DeferQueue` with space for at least `capacity` states before
+    /// reallocating. The space is reused after each `execute`.
+    pub fn with_capacity(capacity: usize) -> Self {
+        DeferQueue {
+            queue: UnsafeCell::new(Vec::with_capacity(capacity)),
+            ..DeferQueue::new()
+        }
+    }
+
+    /// Returns the number of pending states.
+    pub fn len(&self) -> usize { self.len.load(Ordering::SeqCst) }
+
+    /// Returns true if there are no pending states.
+    pub fn is_empty(&self) -> bool { self.len() == 0 }
+
+    /// Returns the number of states the queue can hold without reallocating.
+    /// Panics if the lock is held elsewhere.
+    pub fn capacity(&self) -> usize {
+        self.with_lock("capacity", |queue| queue.capacity())
+    }
+
+    /// Adds a state to the end of the queue.
+    /// Panics if the lock is held elsewhere, e.g. if called from within `execute`.
+    pub fn defer(&self, state: S) {
+        self.with_lock("defer", |queue| queue.push(state));
+    }
+
+    /// Discards all pending states without running anything. Keeps the allocated capacity.
+    /// Panics if the lock is held elsewhere.
+    pub fn clear(&self) {
+        self.with_lock("clear", |queue| queue.clear());
+    }
+
+    /// Runs the given closure once for each pending state, oldest first, and empties the queue.
+    /// Returns the number of states processed.
+    /// Panics if the lock is held elsewhere.
+    pub fn execute<F: FnMut(S)>(&self, f: F) -> usize {
+        self.with_lock("execute", |queue| {
+            let count = queue.len();
+            queue.drain(..).for_each(f);
+            count
+        })
+    }
+
+    // runs `f` with exclusive access to the queue, keeping `len` in sync afterwards
+    fn with_lock<R>(&self, name: &str, f: impl FnOnce(&mut Vec<S>) -> R) -> R {
+        let was_locked = self.locked.fetch_or(true, Ordering::SeqCst);
+        if was_locked { panic!("DeferQueue::<{}>::{}() called while lock was already held", std::any::type_name::<S>(), name); }
+        let queue = unsafe { &mut *self.queue.get() };
+        let result = f(queue);
+        self.len.store(queue.len(), Ordering::SeqCst);
+        self.locked.store(false, Ordering::SeqCst);
+        result
+    }
+}
+
+impl<S> Default for DeferQueue<S> {
+    fn default() -> Self { DeferQueue::new() }
+}
+
+impl<S> Debug for DeferQueue<S> {
+    /// Only inspects the atomic fields, so this is safe to call even while another thread holds the lock.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DeferQueue")
+            .field("len", &self.len.load(Ordering::SeqCst))
+            .field("locked", &self.locked.load(Ordering::SeqCst))
+            .finish()
+    }
+}
+
+
+/// Collects commands during an immutable pass over some data and applies them afterwards in a
+/// mutable pass, for the double-&mut situation described on `Defer` when there can be many
+/// changes to make. Backed by a `DeferQueue`, with the same locking behavior.
+///
+/// ```rs
+/// fn update(&mut self) {
+///     for entity in self.entities.iter() {
+///         if entity.health <= 0 {
+///             self.commands.push(Command::Despawn(entity.id)); // only needs &self.commands
+///         }
+///     }
+///     self.commands.flush(|cmd| self.apply(cmd)); // safe to mutate here
+/// }
+/// ```
+pub struct CommandBuffer<C> {
+    queue: DeferQueue<C>,
+}
+
+impl<C> CommandBuffer<C> {
+    /// Constructs a new, empty `CommandBuffer`.
+    pub const fn new() -> Self {
+        CommandBuffer { queue: DeferQueue::new() }
+    }
+
+    /// Returns the number of commands waiting to be flushed.
+    pub fn len(&self) -> usize { self.queue.len() }
+
+    /// Returns true if there are no commands waiting to be flushed.
+    pub fn is_empty(&self) -> bool { self.queue.is_empty() }
+
+    /// Adds a command to the end of the buffer.
+    /// Panics if called from within `flush`.
+    pub fn push(&self, cmd: C) {
+        self.queue.defer(cmd);
+    }
+
+    /// Passes each buffered command to `apply`, in the order they were pushed, and empties the
+    /// buffer. Returns the number of commands applied.
+    /// Panics if called from within another `flush`.
+    pub fn flush(&self, apply: impl FnMut(C)) -> usize {
+        self.queue.execute(apply)
+    }
+}
+
+impl<C> Default for CommandBuffer<C> {
+    fn default() -> Self { CommandBuffer::new() }
+}
+
+impl<C> Debug for CommandBuffer<C> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CommandBuffer")
+            .field("len", &self.queue.len())
+            .finish()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slice_max_min_ord() {
+        let bytes: &[u8] = &[3, 250, 0, 17];
+        assert_eq!(slice_max_ord(bytes), Some(250));
+        assert_eq!(slice_min_ord(bytes), Some(0));
+        assert_eq!(slice_max_ord::<i32>(&[]), None);
+        assert_eq!(slice_min_ord::<i32>(&[]), None);
+    }
+
+    #[test]
+    fn test_array_sum() {
+        assert_eq!(array_sum([2.5f32]), 2.5);
+        assert_eq!(array_sum([1.0f32, 2.0, 3.0, 4.0]), 10.0);
+        assert_eq!(array_sum::<0, f32>([]), 0.0);
+    }
+
+    #[test]
+    fn test_array_mean() {
+        assert_eq!(array_mean([2.5f32]), 2.5);
+        assert_eq!(array_mean([1.0f64, 2.0, 3.0, 4.0]), 2.5);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_array_mean_empty_should_panic() {
+        array_mean::<0, f32>([]);
+    }
+
+    #[test]
+    fn test_normalize_weights() {
+        let mut weights = [1.0, 3.0, 4.0];
+        normalize_weights(&mut weights);
+        assert_eq!(weights, [0.125, 0.375, 0.5]);
+        assert_eq!(normalized_weights(&[2.0, 2.0]), vec![0.5, 0.5]);
+    }
+
+    fn test_frustum() -> FrustumPlanes {
+        let plane = |d: f32| Plane { n: Vector3::new(0.0, 0.0, 1.0), d };
+        FrustumPlanes {
+            left: plane(0.0),
+            right: plane(1.0),
+            bottom: plane(2.0),
+            top: plane(3.0),
+            front: plane(4.0),
+            rear: plane(5.0),
+        }
+    }
+
+    #[test]
+    fn test_classify_sphere() {
+        // a 2x2x2 box around the origin
+        let frustum = FrustumPlanes {
+            left: Plane::new(Vector3::new(-1.0, 0.0, 0.0), 1.0),
+            right: Plane::new(Vector3::new(1.0, 0.0, 0.0), 1.0),
+            bottom: Plane::new(Vector3::new(0.0, -1.0, 0.0), 1.0),
+            top: Plane::new(Vector3::new(0.0, 1.0, 0.0), 1.0),
+            front: Plane::new(Vector3::new(0.0, 0.0, -1.0), 1.0),
+            rear: Plane::new(Vector3::new(0.0, 0.0, 1.0), 1.0),
+        };
+        assert_eq!(frustum.classify_sphere(Point3::new(0.0, 0.0, 0.0), 0.5), Containment::Inside);
+        assert_eq!(frustum.classify_sphere(Point3::new(1.0, 0.0, 0.0), 0.5), Containment::Intersecting);
+        assert_eq!(frustum.classify_sphere(Point3::new(0.0, 3.0, 0.0), 0.5), Containment::Outside);
+    }
+
+    #[test]
+    fn test_frustum_planes_order() {
+        let frustum = test_frustum();
+        let distances: Vec<f32> = frustum.planes().iter().map(|p| p.d).collect();
+        assert_eq!(distances, vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0]);
+        for (i, plane) in (&frustum).into_iter().enumerate() {
+            assert_eq!(plane.d, frustum[i].d);
+        }
+        assert_eq!(frustum.into_iter().count(), 6);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_frustum_planes_index_out_of_bounds() {
+        let _ = test_frustum()[6];
+    }
+
+    #[test]
+    fn test_lerp_angle() {
+        assert!((lerp_angle(350.0, 10.0, 0.5) - 0.0).abs() < 0.0001);
+        assert!((lerp_angle(350.0, 10.0, 0.25) - 355.0).abs() < 0.0001);
+        assert!((lerp_angle(10.0, 350.0, 0.75) - 355.0).abs() < 0.0001);
+        assert!((lerp_angle(30.0, 90.0, 0.5) - 60.0).abs() < 0.0001);
+        assert!((lerp_angle(-90.0, 720.0, 0.0) - 270.0).abs() < 0.0001);
+        assert!((lerp_angle(-90.0, 720.0, 1.0) - 0.0).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_plane_reflect_and_project() {
+        // the plane y = 2, with a non-normalized normal
+        let plane = Plane::new(Vector3::new(0.0, 2.0, 0.0), 4.0);
+        let p = Point3::new(1.0, 5.0, -3.0);
+        assert_eq!(plane.project_point(p), Point3::new(1.0, 2.0, -3.0));
+        assert_eq!(plane.reflect_point(p), Point3::new(1.0, -1.0, -3.0));
+        assert_eq!(plane.reflect_vector(Vector3::new(1.0, -1.0, 0.0)), Vector3::new(1.0, 1.0, 0.0));
+    }
+
+    #[test]
+    fn test_intersect_three_planes() {
+        let x = Plane::new(Vector3::new(1.0, 0.0, 0.0), 1.0);
+        let y = Plane::new(Vector3::new(0.0, 1.0, 0.0), 2.0);
+        let z = Plane::new(Vector3::new(0.0, 0.0, 1.0), 3.0);
+        assert_eq!(intersect_three_planes(x, y, z), Some(Point3::new(1.0, 2.0, 3.0)));
+
+        let x2 = Plane::new(Vector3::new(1.0, 0.0, 0.0), 5.0);
+        assert_eq!(intersect_three_planes(x, x2, z), None);
+    }
+
+    #[test]
+    fn test_points_in_box_matches_scalar() {
+        // simple LCG so the batch is pseudo-random but deterministic
+        let mut state = 12345u32;
+        let mut next = || {
+            state = state.wrapping_mul(1103515245).wrapping_add(12345);
+            (state >> 8) as f32 / (1 << 24) as f32 * 4.0 - 2.0
+        };
+        let points: Vec<[f32; 2]> = (0..1000).map(|_| [next(), next()]).collect();
+        let (mins, maxes) = ([-1.0, -0.5], [1.0, 1.5]);
+
+        let mut out = Vec::new();
+        points_in_box(&points, mins, maxes, &mut out);
+        assert_eq!(out.len(), points.len());
+        for (point, result) in points.iter().zip(out.iter()) {
+            assert_eq!(*result, point_box_intersection(*point, mins, maxes));
+        }
+        assert!(out.iter().any(|r| *r) && out.iter().any(|r| !*r));
+    }
+
+    #[test]
+    fn test_mono_counter_fmt() {
+        let counter = MonoCounter::new();
+        assert_eq!(counter.to_string(), "0");
+        counter.next();
+        counter.next();
+        counter.next();
+        assert_eq!(counter.to_string(), "3");
+        assert_eq!(format!("{:?}", counter), "MonoCounter(3)");
+        assert_eq!(counter.current(), 3);
+    }
+
+    #[test]
+    fn test_mono_counter32() {
+        let counter = MonoCounter32::new();
+        assert_eq!(counter.next(), 0);
+        assert_eq!(counter.next(), 1);
+    }
+
+    #[test]
+    fn test_mono_counter32_wraps() {
+        let counter = MonoCounter32(AtomicU32::new(u32::MAX - 1));
+        assert_eq!(counter.next(), u32::MAX - 1);
+        assert_eq!(counter.next(), u32::MAX);
+        assert_eq!(counter.next(), 0);
+    }
+
+    #[test]
+    fn test_defer_debug() {
+        let defer: Defer<u32> = Defer::new();
+        assert_eq!(format!("{:?}", defer), "Defer { deferred: false, locked: false }");
+        defer.defer(1);
+        assert_eq!(format!("{:?}", defer), "Defer { deferred: true, locked: false }");
+        defer.execute(|_| {
+            assert_eq!(format!("{:?}", defer), "Defer { deferred: false, locked: true }");
+        });
+        assert_eq!(format!("{:?}", defer), "Defer { deferred: false, locked: false }");
+    }
+
+    #[test]
+    fn test_defer_map_deferred() {
+        let defer: Defer<u32> = Defer::new();
+        defer.defer(42);
+        let mapped = defer.map_deferred(|n| format!("value: {}", n));
+        assert!(mapped.is_deferred());
+        let mut result = String::new();
+        assert!(mapped.execute(|s| result = s));
+        assert_eq!(result, "value: 42");
+
+        let empty: Defer<u32> = Defer::new();
+        let mapped = empty.map_deferred(|_| -> String { panic!("shouldn't be called") });
+        assert!(!mapped.is_deferred());
+    }
+
+    #[test]
+    fn test_defer_queue() {
+        let queue = DeferQueue::new();
+        assert!(queue.is_empty());
+        queue.defer(1);
+        queue.defer(2);
+        queue.defer(3);
+        assert_eq!(queue.len(), 3);
+        let mut seen = Vec::new();
+        assert_eq!(queue.execute(|n| seen.push(n)), 3);
+        assert_eq!(seen, vec![1, 2, 3]);
+        assert!(queue.is_empty());
+        assert_eq!(queue.execute(|_| panic!("nothing should be pending")), 0);
+    }
+
+    #[test]
+    fn test_command_buffer() {
+        #[derive(Debug, PartialEq)]
+        enum Command { Add(i32), Remove(usize) }
+
+        let mut values = vec![1, -2, 3, -4];
+        let commands = CommandBuffer::new();
+        for (i, v) in values.iter().enumerate() {
+            if *v < 0 { commands.push(Command::Remove(i)) }
+            else { commands.push(Command::Add(v * 10)) }
+        }
+        assert_eq!(commands.len(), 4);
+
+        let mut applied = Vec::new();
+        let count = commands.flush(|cmd| {
+            match cmd {
+                Command::Add(v) => values.push(v),
+                Command::Remove(i) => values[i] = 0,
+            }
+            applied.push(values.len());
+        });
+        assert_eq!(count, 4);
+        assert_eq!(values, vec![1, 0, 3, 0, 10, 30]);
+        assert_eq!(applied, vec![5, 5, 6, 6]);
+        assert!(commands.is_empty());
+        assert_eq!(commands.flush(|_| panic!("buffer should be empty")), 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_command_buffer_push_during_flush_panics() {
+        let commands = CommandBuffer::new();
+        commands.push(1);
+        commands.flush(|n| commands.push(n + 1));
+    }
+
+    #[test]
+    fn test_defer_queue_capacity_and_clear() {
+        let queue = DeferQueue::with_capacity(1000);
+        assert!(queue.capacity() >= 1000);
+        for i in 0..1000 {
+            queue.defer(i);
+        }
+        assert_eq!(queue.len(), 1000);
+        assert!(queue.capacity() >= 1000);
+
+        queue.clear();
+        assert!(queue.is_empty());
+        assert!(queue.capacity() >= 1000);
+        assert_eq!(queue.execute(|_| panic!("cleared items shouldn't run")), 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_defer_queue_reentrant_defer_panics() {
+        let queue = DeferQueue::new();
+        queue.defer(1);
+        queue.execute(|n| queue.defer(n + 1));
+    }
+
+    #[test]
+    fn test_normalize_weights_all_zero() {
+        let mut weights = [0.0; 4];
+        normalize_weights(&mut weights);
+        assert_eq!(weights, [0.25; 4]);
+    }
 }
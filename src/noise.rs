@@ -1,12 +1,44 @@
 use noise::{Perlin, Seedable, NoiseFn};
+use crate::lerp;
+
+/// Interpolation curve used to blend between lattice values, from blockiest to smoothest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interpolation {
+    /// Straight linear interpolation. Continuous, but with visible creases along the lattice.
+    Linear,
+    /// Cubic smoothstep, `3t² - 2t³`. Has a continuous first derivative.
+    Smoothstep,
+    /// Quintic fade, `6t⁵ - 15t⁴ + 10t³`, as used by Perlin noise. Has continuous first and second derivatives.
+    Quintic,
+}
+
+impl Interpolation {
+    fn apply(self, t: f32) -> f32 {
+        match self {
+            Interpolation::Linear => t,
+            Interpolation::Smoothstep => t * t * (3.0 - 2.0 * t),
+            Interpolation::Quintic => t * t * t * (t * (t * 6.0 - 15.0) + 10.0),
+        }
+    }
+}
 
 struct Octave {
     pub generator: Perlin,
+    pub seed: u32,
     pub scale: f32,
     pub influence: f32,
 }
 
+impl Octave {
+    // samples this octave's generator at the given frequency, using the same coordinate mapping as `value`
+    fn sample(&self, scale: f32, x: f32, y: f32) -> f32 {
+        let (scaled_x, scaled_y) = (x * scale + scale * 100.0, y * scale - scale * 100.0);
+        self.generator.get([scaled_x as f64, scaled_y as f64]) as f32
+    }
+}
+
 pub struct OctavePerlinNoise {
+    seed: u32,
     octaves: Vec<Octave>
 }
 
@@ -19,19 +51,384 @@ impl OctavePerlinNoise {
             p.set_seed(seed_u64 as u32);
             octaves.push(Octave {
                 generator: p,
+                seed: seed_u64 as u32,
                 scale: 0.0071 * spread.powf(i as f32),
                 influence: persistence.powf(i as f32),
             });
         }
-        Self { octaves }
+        Self { seed, octaves }
     }
 
     pub fn value(&self, x: f32, y: f32) -> f32 {
         let mut sum: f32 = 0.0;
+        for o in self.octaves.iter() {
+            sum += o.sample(o.scale, x, y) * o.influence;
+        }
+        sum
+    }
+
+    /// Fractal gradient noise with the same octave frequencies and influences as `value`, along with
+    /// its analytic partial derivatives with respect to `x` and `y`, e.g. for generating normals from
+    /// a heightmap. The lattice gradients are derived from this noise's seed rather than taken from
+    /// the `noise` crate, so the returned value differs from `value`; use it as the height when the
+    /// gradient needs to match.
+    pub fn sample_with_gradient(&self, x: f32, y: f32) -> (f32, [f32; 2]) {
+        let mut sum: f32 = 0.0;
+        let mut gradient = [0.0f32; 2];
         for o in self.octaves.iter() {
             let (scaled_x, scaled_y) = (x * o.scale + o.scale * 100.0, y * o.scale - o.scale * 100.0);
-            sum += o.generator.get([scaled_x as f64, scaled_y as f64]) as f32 * o.influence;
+            let (value, [dx, dy]) = gradient_noise_2d(o.seed, scaled_x, scaled_y);
+            sum += value * o.influence;
+            // chain rule for the octave's scaling of the input
+            gradient[0] += dx * o.scale * o.influence;
+            gradient[1] += dy * o.scale * o.influence;
+        }
+        (sum, gradient)
+    }
+
+    /// Samples the noise after offsetting `(x, y)` by a vector derived from the noise itself,
+    /// producing swirly, organic-looking patterns. `strength` scales the offset; zero gives the
+    /// same result as `value`.
+    pub fn domain_warp(&self, x: f32, y: f32, strength: f32) -> f32 {
+        // sample far-off regions of the noise so the two offset components are uncorrelated
+        let warp_x = self.value(x + 5200.0, y + 1300.0);
+        let warp_y = self.value(x - 1700.0, y + 9200.0);
+        self.value(x + warp_x * strength, y + warp_y * strength)
+    }
+
+    /// Ridged multifractal noise, which has sharp ridges where the underlying noise crosses zero,
+    /// e.g. for mountain ranges. Sums `octaves` layers of `1 - |noise|`, where each layer's frequency
+    /// is multiplied by `lacunarity` and its amplitude by `gain`. Layers use the same generators and
+    /// coordinate mapping as `value`, with the first layer at the first octave's frequency, so with
+    /// `lacunarity == spread` the layers line up with `value`'s octaves. `octaves` is capped at the
+    /// number of octaves this noise was created with. The result is in `[0, 1 + gain + gain² + ...]`.
+    pub fn ridged(&self, x: f32, y: f32, octaves: u8, lacunarity: f32, gain: f32) -> f32 {
+        self.fractal(x, y, octaves, lacunarity, gain, |n| 1.0 - n.abs())
+    }
+
+    /// Billowy fractal noise, with rounded puffs like clouds or rolling hills. The same as `ridged`
+    /// but sums `|noise|` instead, which puts the creases in the valleys.
+    pub fn billow(&self, x: f32, y: f32, octaves: u8, lacunarity: f32, gain: f32) -> f32 {
+        self.fractal(x, y, octaves, lacunarity, gain, f32::abs)
+    }
+
+    // sums up to `octaves` layers of noise passed through `shape`, one per octave generator
+    fn fractal(&self, x: f32, y: f32, octaves: u8, lacunarity: f32, gain: f32, shape: impl Fn(f32) -> f32) -> f32 {
+        let Some(first) = self.octaves.first() else { return 0.0 };
+        let (mut sum, mut frequency, mut amplitude) = (0.0f32, first.scale, 1.0f32);
+        for o in self.octaves.iter().take(octaves as usize) {
+            sum += shape(o.sample(frequency, x, y)) * amplitude;
+            frequency *= lacunarity;
+            amplitude *= gain;
         }
         sum
     }
-}
\ No newline at end of file
+
+    /// Value noise, with a single octave on a unit lattice. Each lattice point is assigned a random
+    /// value in `[-1, 1]` based on the seed, and points in between are blended using `interpolation`.
+    /// Blockier than the gradient noise from `value`.
+    pub fn value_noise(&self, x: f32, y: f32, interpolation: Interpolation) -> f32 {
+        let (x0, y0) = (x.floor(), y.floor());
+        let (ix, iy) = (x0 as i32, y0 as i32);
+        let (u, v) = (interpolation.apply(x - x0), interpolation.apply(y - y0));
+        // wrapping, since the lattice coordinates saturate for huge inputs
+        let (ix1, iy1) = (ix.wrapping_add(1), iy.wrapping_add(1));
+        let top = lerp(self.lattice_value(ix, iy), self.lattice_value(ix1, iy), u);
+        let bottom = lerp(self.lattice_value(ix, iy1), self.lattice_value(ix1, iy1), u);
+        lerp(top, bottom, v)
+    }
+
+    fn lattice_value(&self, x: i32, y: i32) -> f32 {
+        // mix the seed so the lattice isn't correlated with the worley feature points
+        let h = hash_2d(self.seed ^ 0x5bd1_e995, x, y);
+        h as f32 / u32::MAX as f32 * 2.0 - 1.0
+    }
+
+    /// Tileable 3D gradient noise, with a single octave on a unit lattice. The lattice wraps every
+    /// `period_x`, `period_y`, and `period_z` units, so the volume repeats seamlessly along each
+    /// axis, e.g. for looping cloud or smoke textures. Periods of zero are treated as one. The
+    /// result is roughly in `[-1, 1]`, and is zero at every lattice point.
+    pub fn sample_tiled_3d(&self, x: f32, y: f32, z: f32, period_x: u32, period_y: u32, period_z: u32) -> f32 {
+        let (x0, y0, z0) = (x.floor(), y.floor(), z.floor());
+        let (fx, fy, fz) = (x - x0, y - y0, z - z0);
+        let wrap = |i: f32, offset: i64, period: u32| (i as i64 + offset).rem_euclid(period.max(1) as i64) as i32;
+        let corner = |dx: i64, dy: i64, dz: i64| -> f32 {
+            let [gx, gy, gz] = self.lattice_gradient_3d(wrap(x0, dx, period_x), wrap(y0, dy, period_y), wrap(z0, dz, period_z));
+            gx * (fx - dx as f32) + gy * (fy - dy as f32) + gz * (fz - dz as f32)
+        };
+
+        let fade = |t: f32| Interpolation::Quintic.apply(t);
+        let (u, v, w) = (fade(fx), fade(fy), fade(fz));
+        let near = lerp(lerp(corner(0, 0, 0), corner(1, 0, 0), u), lerp(corner(0, 1, 0), corner(1, 1, 0), u), v);
+        let far = lerp(lerp(corner(0, 0, 1), corner(1, 0, 1), u), lerp(corner(0, 1, 1), corner(1, 1, 1), u), v);
+        lerp(near, far, w)
+    }
+
+    // picks one of the 12 cube edge directions, as in improved Perlin noise
+    fn lattice_gradient_3d(&self, x: i32, y: i32, z: i32) -> [f32; 3] {
+        const GRADIENTS: [[f32; 3]; 12] = [
+            [1.0, 1.0, 0.0], [-1.0, 1.0, 0.0], [1.0, -1.0, 0.0], [-1.0, -1.0, 0.0],
+            [1.0, 0.0, 1.0], [-1.0, 0.0, 1.0], [1.0, 0.0, -1.0], [-1.0, 0.0, -1.0],
+            [0.0, 1.0, 1.0], [0.0, -1.0, 1.0], [0.0, 1.0, -1.0], [0.0, -1.0, -1.0],
+        ];
+        let h = hash_2d((self.seed ^ 0x68e3_1da4).wrapping_add((z as u32).wrapping_mul(0x9e37_79b9)), x, y);
+        GRADIENTS[(h % 12) as usize]
+    }
+
+    /// Cellular (Worley) noise. Returns the distance from `(x, y)` to the nearest feature point.
+    /// There is one feature point per unit cell, placed deterministically based on the seed.
+    pub fn worley(&self, x: f32, y: f32) -> f32 {
+        self.worley_distances(x, y).0
+    }
+
+    /// Cellular (Worley) noise. Returns the difference between the distances to the second-nearest
+    /// and nearest feature points (F2 - F1), which produces a pattern of cell borders.
+    pub fn worley_f2_f1(&self, x: f32, y: f32) -> f32 {
+        let (f1, f2) = self.worley_distances(x, y);
+        f2 - f1
+    }
+
+    fn worley_distances(&self, x: f32, y: f32) -> (f32, f32) {
+        let (cell_x, cell_y) = (x.floor() as i32, y.floor() as i32);
+        let mut f1 = f32::INFINITY;
+        let mut f2 = f32::INFINITY;
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                // wrapping, since the cell saturates for huge coordinates
+                let [px, py] = self.feature_point(cell_x.wrapping_add(dx), cell_y.wrapping_add(dy));
+                let dist = ((px - x).powi(2) + (py - y).powi(2)).sqrt();
+                if dist < f1 {
+                    f2 = f1;
+                    f1 = dist;
+                }
+                else if dist < f2 {
+                    f2 = dist;
+                }
+            }
+        }
+        (f1, f2)
+    }
+
+    fn feature_point(&self, cell_x: i32, cell_y: i32) -> [f32; 2] {
+        let h = hash_2d(self.seed, cell_x, cell_y);
+        let jitter_x = (h & 0xffff) as f32 / 65536.0;
+        let jitter_y = (h >> 16) as f32 / 65536.0;
+        [cell_x as f32 + jitter_x, cell_y as f32 + jitter_y]
+    }
+}
+
+// 2D gradient noise on a unit lattice and its partial derivatives. Each corner gets one of eight
+// gradients from `hash_2d`, and the corner contributions are blended with the quintic fade, so the
+// derivative is just the product rule applied to that blend.
+fn gradient_noise_2d(seed: u32, x: f32, y: f32) -> (f32, [f32; 2]) {
+    const GRADIENTS: [[f32; 2]; 8] = [
+        [1.0, 1.0], [-1.0, 1.0], [1.0, -1.0], [-1.0, -1.0],
+        [1.0, 0.0], [-1.0, 0.0], [0.0, 1.0], [0.0, -1.0],
+    ];
+    let (x0, y0) = (x.floor(), y.floor());
+    let (fx, fy) = (x - x0, y - y0);
+    let (ix, iy) = (x0 as i32, y0 as i32);
+    let gradient = |dx: i32, dy: i32| GRADIENTS[(hash_2d(seed, ix.wrapping_add(dx), iy.wrapping_add(dy)) % 8) as usize];
+    let (g00, g10, g01, g11) = (gradient(0, 0), gradient(1, 0), gradient(0, 1), gradient(1, 1));
+    let n00 = g00[0] * fx + g00[1] * fy;
+    let n10 = g10[0] * (fx - 1.0) + g10[1] * fy;
+    let n01 = g01[0] * fx + g01[1] * (fy - 1.0);
+    let n11 = g11[0] * (fx - 1.0) + g11[1] * (fy - 1.0);
+
+    let (u, v) = (Interpolation::Quintic.apply(fx), Interpolation::Quintic.apply(fy));
+    let fade_derivative = |t: f32| 30.0 * t * t * (t - 1.0) * (t - 1.0);
+    let (k1, k2, k3) = (n10 - n00, n01 - n00, n00 + n11 - n10 - n01);
+    let value = n00 + k1 * u + k2 * v + k3 * u * v;
+    // the corner terms are linear in (x, y), so their derivatives are just the gradient components
+    let blend = |axis: usize| g00[axis] + (g10[axis] - g00[axis]) * u + (g01[axis] - g00[axis]) * v
+        + (g00[axis] + g11[axis] - g10[axis] - g01[axis]) * u * v;
+    let dx = blend(0) + (k1 + k3 * v) * fade_derivative(fx);
+    let dy = blend(1) + (k2 + k3 * u) * fade_derivative(fy);
+    (value, [dx, dy])
+}
+
+// simple integer hash for deterministic lattice values
+fn hash_2d(seed: u32, x: i32, y: i32) -> u32 {
+    let mut h = seed
+        .wrapping_add((x as u32).wrapping_mul(0x27d4_eb2d))
+        .wrapping_add((y as u32).wrapping_mul(0x1656_67b1));
+    h ^= h >> 15;
+    h = h.wrapping_mul(0x85eb_ca6b);
+    h ^= h >> 13;
+    h = h.wrapping_mul(0xc2b2_ae35);
+    h ^= h >> 16;
+    h
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{gradient_noise_2d, Interpolation, OctavePerlinNoise};
+
+    #[test]
+    fn test_worley_zero_at_feature_point() {
+        let noise = OctavePerlinNoise::new(7, 1, 2.0, 0.5);
+        let [px, py] = noise.feature_point(3, -2);
+        assert_eq!(noise.worley(px, py), 0.0);
+        // moving away from the feature point increases the distance
+        let near = noise.worley(px + 0.01, py);
+        let far = noise.worley(px + 0.05, py);
+        assert!(near > 0.0);
+        assert!(far > near);
+    }
+
+    #[test]
+    fn test_domain_warp() {
+        let noise = OctavePerlinNoise::new(7, 3, 2.0, 0.5);
+        assert_eq!(noise.domain_warp(12.5, -40.25, 0.0), noise.value(12.5, -40.25));
+        assert_ne!(noise.domain_warp(12.5, -40.25, 100.0), noise.value(12.5, -40.25));
+    }
+
+    #[test]
+    fn test_sample_with_gradient() {
+        let noise = OctavePerlinNoise::new(3, 4, 2.0, 0.5);
+        let value = |x: f32, y: f32| noise.sample_with_gradient(x, y).0;
+        let h = 0.01;
+        for &(x, y) in &[(0.0, 0.0), (12.5, -40.25), (-300.0, 77.7), (1234.5, 987.6)] {
+            let (_, [dx, dy]) = noise.sample_with_gradient(x, y);
+            let fd_x = (value(x + h, y) - value(x - h, y)) / (2.0 * h);
+            let fd_y = (value(x, y + h) - value(x, y - h)) / (2.0 * h);
+            assert!((dx - fd_x).abs() < 0.001, "d/dx at ({}, {}): {} vs {}", x, y, dx, fd_x);
+            assert!((dy - fd_y).abs() < 0.001, "d/dy at ({}, {}): {} vs {}", x, y, dy, fd_y);
+        }
+        // the seed changes the lattice gradients
+        let other = OctavePerlinNoise::new(4, 4, 2.0, 0.5);
+        assert_ne!(noise.sample_with_gradient(12.5, -40.25), other.sample_with_gradient(12.5, -40.25));
+    }
+
+    #[test]
+    fn test_gradient_noise_2d() {
+        let h = 0.001;
+        let mut nonzero = false;
+        for i in 0..500 {
+            let (x, y) = (i as f32 * 0.1373 - 37.0, i as f32 * -0.0917 + 15.0);
+            let (value, [dx, dy]) = gradient_noise_2d(9, x, y);
+            assert!((-1.0..=1.0).contains(&value), "{} out of range at ({}, {})", value, x, y);
+            let fd_x = (gradient_noise_2d(9, x + h, y).0 - gradient_noise_2d(9, x - h, y).0) / (2.0 * h);
+            let fd_y = (gradient_noise_2d(9, x, y + h).0 - gradient_noise_2d(9, x, y - h).0) / (2.0 * h);
+            assert!((dx - fd_x).abs() < 0.01, "d/dx at ({}, {}): {} vs {}", x, y, dx, fd_x);
+            assert!((dy - fd_y).abs() < 0.01, "d/dy at ({}, {}): {} vs {}", x, y, dy, fd_y);
+            nonzero |= value.abs() > 0.05;
+        }
+        assert!(nonzero);
+        // zero at every lattice point, where the gradient is the corner's own gradient
+        for (x, y) in [(0, 0), (3, -7), (-12, 40)] {
+            let (value, [dx, dy]) = gradient_noise_2d(9, x as f32, y as f32);
+            assert_eq!(value, 0.0);
+            assert!(dx != 0.0 || dy != 0.0);
+        }
+    }
+
+    #[test]
+    fn test_value_noise() {
+        let noise = OctavePerlinNoise::new(11, 1, 2.0, 0.5);
+        let modes = [Interpolation::Linear, Interpolation::Smoothstep, Interpolation::Quintic];
+        for mode in modes {
+            for (x, y) in [(0, 0), (3, -7), (-12, 40)] {
+                assert_eq!(noise.value_noise(x as f32, y as f32, mode), noise.lattice_value(x, y));
+            }
+        }
+        for i in 0..500 {
+            let (x, y) = (i as f32 * 0.37 - 90.0, i as f32 * -0.61 + 13.0);
+            for mode in modes {
+                let v = noise.value_noise(x, y, mode);
+                assert!((-1.0..=1.0).contains(&v));
+            }
+        }
+        // halfway between lattice points all modes agree
+        let a = noise.value_noise(2.5, 4.0, Interpolation::Linear);
+        let b = noise.value_noise(2.5, 4.0, Interpolation::Quintic);
+        assert!((a - b).abs() < 0.00001);
+        assert_eq!(noise.value_noise(1.3, 2.7, Interpolation::Smoothstep), OctavePerlinNoise::new(11, 1, 2.0, 0.5).value_noise(1.3, 2.7, Interpolation::Smoothstep));
+        // huge coordinates saturate the lattice instead of overflowing
+        for (x, y) in [(3.0e9, 0.0), (0.5, -3.0e9), (f32::MAX, f32::MAX)] {
+            assert!((-1.0..=1.0).contains(&noise.value_noise(x, y, Interpolation::Quintic)));
+        }
+    }
+
+    #[test]
+    fn test_sample_tiled_3d() {
+        let noise = OctavePerlinNoise::new(21, 1, 2.0, 0.5);
+        let (px, py, pz) = (4, 3, 5);
+        let mut nonzero = false;
+        for i in 0..200 {
+            let (a, b) = (i as f32 * 0.173 % 1.0 * 4.0, i as f32 * 0.311 % 1.0 * 5.0);
+            // opposite faces of the period box match on each axis
+            let x_face = (noise.sample_tiled_3d(0.0, a, b, px, py, pz), noise.sample_tiled_3d(px as f32, a, b, px, py, pz));
+            let y_face = (noise.sample_tiled_3d(a, 0.0, b, px, py, pz), noise.sample_tiled_3d(a, py as f32, b, px, py, pz));
+            let z_face = (noise.sample_tiled_3d(a, b, 0.0, px, py, pz), noise.sample_tiled_3d(a, b, pz as f32, px, py, pz));
+            for (near, far) in [x_face, y_face, z_face] {
+                assert!((near - far).abs() < 0.0001, "{} vs {}", near, far);
+            }
+            // and the whole volume repeats, including into negative coordinates
+            let (x, y, z) = (a + 0.37, b * 0.5 + 0.11, b - 0.29);
+            let v = noise.sample_tiled_3d(x, y, z, px, py, pz);
+            let shifted = noise.sample_tiled_3d(x - 2.0 * px as f32, y + py as f32, z - pz as f32, px, py, pz);
+            assert!((v - shifted).abs() < 0.0001);
+            assert!((-1.5..=1.5).contains(&v));
+            nonzero |= v.abs() > 0.05;
+        }
+        assert!(nonzero);
+    }
+
+    #[test]
+    fn test_ridged_and_billow() {
+        let noise = OctavePerlinNoise::new(5, 4, 2.0, 0.5);
+        let gain_sum = 1.0 + 0.5 + 0.25 + 0.125;
+        let mut differs = false;
+        for i in 0..200 {
+            let (x, y) = (i as f32 * 13.7 - 900.0, i as f32 * 29.1 + 300.0);
+            let fbm = noise.value(x, y);
+            let ridged = noise.ridged(x, y, 4, 2.0, 0.5);
+            let billow = noise.billow(x, y, 4, 2.0, 0.5);
+            assert!(ridged >= 0.0 && ridged <= gain_sum + 0.0001, "ridged {} out of range", ridged);
+            assert!(billow >= 0.0 && billow <= gain_sum + 0.0001, "billow {} out of range", billow);
+            // with matching lacunarity and gain the layers are fBm's octaves, so per-layer
+            // |n| and 1 - |n| sum to the gain sum, and the triangle inequality bounds billow below
+            assert!((ridged + billow - gain_sum).abs() < 0.0001);
+            assert!(billow >= fbm.abs() - 0.0001);
+            if (billow - fbm.abs()).abs() > 0.01 { differs = true }
+        }
+        assert!(differs);
+
+        // a single octave is just |fBm|
+        let single = OctavePerlinNoise::new(5, 1, 2.0, 0.5);
+        for i in 0..50 {
+            let (x, y) = (i as f32 * 7.3 - 40.0, i as f32 * -3.9 + 11.0);
+            assert_eq!(single.billow(x, y, 1, 2.0, 0.5), single.value(x, y).abs());
+            assert_eq!(noise.billow(x, y, 1, 3.0, 0.1), noise.octaves[0].sample(noise.octaves[0].scale, x, y).abs());
+        }
+
+        // octaves are capped at the number the noise was created with
+        assert_eq!(noise.ridged(1.3, 2.6, 4, 2.0, 0.5), noise.ridged(1.3, 2.6, 9, 2.0, 0.5));
+        assert_eq!(noise.ridged(1.3, 2.6, 0, 2.0, 0.5), 0.0);
+        assert_eq!(OctavePerlinNoise::new(5, 0, 2.0, 0.5).billow(1.3, 2.6, 3, 2.0, 0.5), 0.0);
+    }
+
+    #[test]
+    fn test_worley_deterministic() {
+        let a = OctavePerlinNoise::new(42, 1, 2.0, 0.5);
+        let b = OctavePerlinNoise::new(42, 1, 2.0, 0.5);
+        let c = OctavePerlinNoise::new(43, 1, 2.0, 0.5);
+        assert_eq!(a.worley(1.3, 7.9), b.worley(1.3, 7.9));
+        assert_eq!(a.worley_f2_f1(1.3, 7.9), b.worley_f2_f1(1.3, 7.9));
+        assert_ne!(a.worley(1.3, 7.9), c.worley(1.3, 7.9));
+        assert!(a.worley_f2_f1(1.3, 7.9) >= 0.0);
+    }
+
+    #[test]
+    fn test_worley_huge_coordinates() {
+        let noise = OctavePerlinNoise::new(42, 1, 2.0, 0.5);
+        for (x, y) in [(3.0e9, 0.0), (-3.0e9, 1.5)] {
+            assert!(noise.worley(x, y) >= 0.0, "at ({}, {})", x, y);
+            assert!(noise.worley_f2_f1(x, y) >= 0.0, "at ({}, {})", x, y);
+        }
+        // distances overflow to infinity out here, but it still shouldn't panic
+        noise.worley(0.5, f32::MAX);
+        noise.worley_f2_f1(f32::MIN, f32::MIN);
+    }
+}
@@ -6,7 +6,8 @@ mod tests;
 
 use std::cell::UnsafeCell;
 use std::fmt::{Debug, Display, Formatter};
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 
 
 /// A simple primitive for ensuring something is done exactly once. Not thread-safe.
@@ -74,6 +75,79 @@ unsafe impl Send for DoOnceSync {}
 unsafe impl Sync for DoOnceSync {}
 
 
+/// A thread-safe throttle that allows one out of every `every` calls, starting with the first.
+/// Useful for things like "log this at most once per N occurrences".
+///
+/// ```
+/// # use toolbelt::once::RateLimit;
+/// let limit = RateLimit::new(3);
+/// let allowed: Vec<bool> = (0..7).map(|_| limit.allow()).collect();
+/// assert_eq!(allowed, [true, false, false, true, false, false, true]);
+/// ```
+pub struct RateLimit {
+    every: u64,
+    count: AtomicU64,
+}
+impl RateLimit {
+    /// Creates a new RateLimit allowing one out of every `every` calls. An `every` of 0 is treated as 1.
+    pub const fn new(every: u64) -> Self {
+        RateLimit { every: if every == 0 { 1 } else { every }, count: AtomicU64::new(0) }
+    }
+
+    /// Returns true on the first call and every `every`-th call after that.
+    pub fn allow(&self) -> bool {
+        self.count.fetch_add(1, Ordering::SeqCst).is_multiple_of(self.every)
+    }
+}
+
+
+/// A thread-safe throttle that allows at most one call per `interval`, using timestamps provided by
+/// the caller (e.g. milliseconds since startup, or a frame counter). The first call is always allowed.
+pub struct RateLimitTime {
+    interval: u64,
+    // timestamp of the last allowed call, or u64::MAX if there hasn't been one
+    last: AtomicU64,
+}
+impl RateLimitTime {
+    pub const fn new(interval: u64) -> Self {
+        RateLimitTime { interval, last: AtomicU64::new(u64::MAX) }
+    }
+
+    /// Returns true if at least `interval` has passed since the last allowed call.
+    pub fn allow(&self, now: u64) -> bool {
+        let mut last = self.last.load(Ordering::SeqCst);
+        loop {
+            if last != u64::MAX && now.saturating_sub(last) < self.interval {
+                return false;
+            }
+            match self.last.compare_exchange(last, now, Ordering::SeqCst, Ordering::SeqCst) {
+                Ok(_) => return true,
+                Err(actual) => last = actual,
+            }
+        }
+    }
+}
+
+
+/// Error returned by `InitOnce::get_or_try_init`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InitError<E> {
+    /// The init closure returned an error. The InitOnce is left uninitialized.
+    Failed(E),
+    /// The value is currently being initialized elsewhere.
+    Locked,
+}
+
+impl<E: Display> Display for InitError<E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InitError::Failed(e) => write!(f, "InitOnce initialization failed: {}", e),
+            InitError::Locked => write!(f, "InitOnce is currently being initialized elsewhere"),
+        }
+    }
+}
+
+
 /// A simple once-initialized immutable-ish reference for easy global statics.
 ///
 /// ```rs
@@ -133,6 +207,29 @@ impl<T> InitOnce<T> {
         Ok(self.get())
     }
 
+    /// Like `get_or_init`, but the closure may fail. If it does, the error is returned as
+    /// `InitError::Failed` and the value stays uninitialized, so a later call can try again.
+    /// Returns `InitError::Locked` if the value is currently being initialized on another thread.
+    pub fn get_or_try_init<E, F>(&self, func: F) -> Result<&T, InitError<E>> where F: FnOnce() -> Result<T, E> {
+        if self.lock.swap(true, Ordering::SeqCst) {
+            return Err(InitError::Locked);
+        }
+        unsafe {
+            let ptr = self.inner.get();
+            if (*ptr).is_none() {
+                match func() {
+                    Ok(value) => ptr.write(Some(value)),
+                    Err(e) => {
+                        self.lock.store(false, Ordering::SeqCst);
+                        return Err(InitError::Failed(e));
+                    }
+                }
+            }
+        }
+        self.lock.store(false, Ordering::SeqCst);
+        Ok(self.get())
+    }
+
     /// Inserts a value into this InitOnce if it's not already initialized.
     /// Utilizes interior mutability so only `&self` is required.
     /// If already initialized, ignores the new value and returns Err.
@@ -176,3 +273,121 @@ impl <T: Display> Display for InitOnce<T> {
 
 unsafe impl<T: Send> Send for InitOnce<T> {}
 unsafe impl<T: Sync> Sync for InitOnce<T> {}
+
+
+/// A fixed-size table of independently lazily-initialized values, e.g. a cache with one slot per
+/// enum variant. Each slot behaves like its own `InitOnce`, without needing `N` separate statics.
+///
+/// ```
+/// # use toolbelt::once::InitOnceArray;
+/// static TABLE: InitOnceArray<String, 4> = InitOnceArray::new();
+/// assert_eq!(TABLE.get_or_init(2, || "two".to_string()), "two");
+/// assert_eq!(TABLE.try_get(2).map(String::as_str), Some("two"));
+/// assert_eq!(TABLE.try_get(0), None);
+/// ```
+pub struct InitOnceArray<T, const N: usize> {
+    slots: [InitOnce<T>; N],
+}
+impl<T, const N: usize> InitOnceArray<T, N> {
+    /// Creates a new table with every slot uninitialized. This `fn` is `const` so it can be used in statics.
+    pub const fn new() -> Self {
+        InitOnceArray { slots: [const { InitOnce::uninitialized() }; N] }
+    }
+
+    /// Retrieves a reference to the value in slot `idx`, calling `func` to initialize it first if
+    /// needed. If the slot is being initialized on another thread, waits for that to finish instead.
+    /// Panics if `idx >= N`. Calling this re-entrantly for the same slot from inside `func` will
+    /// never return.
+    pub fn get_or_init<F>(&self, idx: usize, func: F) -> &T where F: FnOnce() -> T {
+        let slot = &self.slots[idx];
+        // the closure is only consumed if the slot actually gets initialized by this call
+        let mut func = Some(func);
+        loop {
+            if let Ok(value) = slot.get_or_init(|| (func.take().unwrap())()) {
+                return value;
+            }
+            std::thread::yield_now();
+        }
+    }
+
+    /// Returns a reference to the value in slot `idx`, or `None` if it's uninitialized or currently
+    /// being initialized elsewhere. Panics if `idx >= N`.
+    pub fn try_get(&self, idx: usize) -> Option<&T> {
+        self.slots[idx].try_get()
+    }
+}
+
+impl<T, const N: usize> Default for InitOnceArray<T, N> {
+    fn default() -> Self { InitOnceArray::new() }
+}
+
+
+// every CallTracker that has been hit at least once, in the order they were first hit
+static CALL_TRACKERS: Mutex<Vec<&'static CallTracker>> = Mutex::new(Vec::new());
+
+/// A thread-safe, labeled hit counter for seeing how often a code path runs, e.g. while profiling.
+/// Trackers are meant to live in statics, and register themselves the first time they're hit so
+/// `CallTracker::dump` can report on all of them. Unlike `MonoCounter`, the count can be reset.
+///
+/// ```
+/// # use toolbelt::once::CallTracker;
+/// static REBUILDS: CallTracker = CallTracker::new("mesh rebuilds");
+/// for _ in 0..3 {
+///     REBUILDS.hit();
+/// }
+/// assert_eq!(REBUILDS.count(), 3);
+/// assert!(CallTracker::dump().contains(&("mesh rebuilds", 3)));
+/// ```
+pub struct CallTracker {
+    label: &'static str,
+    count: AtomicU64,
+    registered: AtomicBool,
+}
+impl CallTracker {
+    /// Creates a new tracker with a count of zero. This `fn` is `const` so it can be used in statics.
+    pub const fn new(label: &'static str) -> Self {
+        CallTracker { label, count: AtomicU64::new(0), registered: AtomicBool::new(false) }
+    }
+
+    /// Returns the label this tracker was created with.
+    pub fn label(&self) -> &'static str { self.label }
+
+    /// Records a hit and returns the new count. Registers the tracker on the first hit.
+    pub fn hit(&'static self) -> u64 {
+        if !self.registered.swap(true, Ordering::SeqCst) {
+            CALL_TRACKERS.lock().unwrap_or_else(|e| e.into_inner()).push(self);
+        }
+        self.count.fetch_add(1, Ordering::SeqCst) + 1
+    }
+
+    /// Returns the number of hits since creation or the last `reset`.
+    pub fn count(&self) -> u64 { self.count.load(Ordering::SeqCst) }
+
+    /// Sets the count back to zero and returns what it was. The tracker stays registered.
+    pub fn reset(&self) -> u64 { self.count.swap(0, Ordering::SeqCst) }
+
+    /// Returns the label and current count of every registered tracker, in the order they were
+    /// first hit.
+    pub fn dump() -> Vec<(&'static str, u64)> {
+        CALL_TRACKERS.lock().unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .map(|tracker| (tracker.label, tracker.count()))
+            .collect()
+    }
+
+    /// Resets every registered tracker.
+    pub fn reset_all() {
+        for tracker in CALL_TRACKERS.lock().unwrap_or_else(|e| e.into_inner()).iter() {
+            tracker.reset();
+        }
+    }
+}
+
+impl Debug for CallTracker {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CallTracker")
+            .field("label", &self.label)
+            .field("count", &self.count())
+            .finish()
+    }
+}
@@ -83,3 +83,116 @@ fn InitOnce_reentrant_init_should_panic() {
     let cell: InitOnce<u32> = InitOnce::uninitialized();
     cell.get_or_init(|| { cell.initialize(1).unwrap(); 1 }).unwrap();
 }
+
+#[test]
+fn InitOnce_get_or_try_init_failure_then_retry() {
+    let cell: InitOnce<u32> = InitOnce::uninitialized();
+    assert_eq!(cell.get_or_try_init(|| Err("file not found")), Err(InitError::Failed("file not found")));
+    assert!(cell.try_get().is_none());
+    assert_eq!(cell.get_or_try_init::<&str, _>(|| Ok(1)), Ok(&1));
+    assert_eq!(cell.get_or_try_init(|| Err("not called")), Ok(&1));
+    assert_eq!(cell.get(), &1);
+}
+
+#[test]
+fn InitOnce_get_or_try_init_reentrant_is_locked() {
+    let cell: InitOnce<u32> = InitOnce::uninitialized();
+    let result = cell.get_or_try_init(|| {
+        assert_eq!(cell.get_or_try_init::<(), _>(|| Ok(2)), Err(InitError::Locked));
+        Ok::<_, ()>(1)
+    });
+    assert_eq!(result, Ok(&1));
+}
+
+#[test]
+fn RateLimit_allows_every_nth_call() {
+    let limit = RateLimit::new(3);
+    let allowed: Vec<usize> = (0..9).filter(|_| limit.allow()).collect();
+    assert_eq!(allowed, vec![0, 3, 6]);
+}
+
+#[test]
+fn RateLimitTime_allows_once_per_interval() {
+    let limit = RateLimitTime::new(10);
+    assert!(limit.allow(5));
+    assert!(!limit.allow(6));
+    assert!(!limit.allow(14));
+    assert!(limit.allow(15));
+    assert!(!limit.allow(20));
+    assert!(limit.allow(100));
+}
+
+#[test]
+fn InitOnceArray_initializes_slots_independently() {
+    let table: InitOnceArray<u32, 4> = InitOnceArray::new();
+    let calls = std::cell::Cell::new(0);
+    let init = |value: u32| { calls.set(calls.get() + 1); value };
+    assert_eq!(table.get_or_init(3, || init(30)), &30);
+    assert_eq!(table.try_get(1), None);
+    assert_eq!(table.get_or_init(1, || init(10)), &10);
+    assert_eq!(table.get_or_init(3, || init(99)), &30);
+    assert_eq!(table.get_or_init(1, || init(99)), &10);
+    assert_eq!(calls.get(), 2);
+    assert_eq!(table.try_get(0), None);
+    assert_eq!(table.try_get(2), None);
+}
+
+#[test]
+fn InitOnceArray_inits_once_across_threads() {
+    static TABLE: InitOnceArray<u64, 2> = InitOnceArray::new();
+    static CALLS: AtomicU64 = AtomicU64::new(0);
+    let handles: Vec<_> = (0..8).map(|i| std::thread::spawn(move || {
+        *TABLE.get_or_init(i % 2, || {
+            CALLS.fetch_add(1, Ordering::SeqCst);
+            std::thread::sleep(std::time::Duration::from_millis(5));
+            (i % 2) as u64 + 100
+        })
+    })).collect();
+    for (i, handle) in handles.into_iter().enumerate() {
+        assert_eq!(handle.join().unwrap(), (i % 2) as u64 + 100);
+    }
+    assert_eq!(CALLS.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+#[should_panic]
+fn InitOnceArray_out_of_bounds_should_panic() {
+    let table: InitOnceArray<u32, 2> = InitOnceArray::new();
+    table.get_or_init(2, || 0);
+}
+
+#[test]
+fn CallTracker_counts_hits_across_threads() {
+    static TRACKER: CallTracker = CallTracker::new("CallTracker_counts_hits_across_threads");
+    assert_eq!(TRACKER.count(), 0);
+    let handles: Vec<_> = (0..8).map(|_| std::thread::spawn(|| {
+        (0..1000).map(|_| TRACKER.hit()).max().unwrap()
+    })).collect();
+    let mut max_seen = 0;
+    for handle in handles {
+        max_seen = max_seen.max(handle.join().unwrap());
+    }
+    assert_eq!(TRACKER.count(), 8000);
+    assert_eq!(max_seen, 8000);
+    assert_eq!(format!("{:?}", TRACKER), "CallTracker { label: \"CallTracker_counts_hits_across_threads\", count: 8000 }");
+}
+
+#[test]
+fn CallTracker_dump_and_reset() {
+    static A: CallTracker = CallTracker::new("CallTracker_dump_and_reset::a");
+    static B: CallTracker = CallTracker::new("CallTracker_dump_and_reset::b");
+    static UNUSED: CallTracker = CallTracker::new("CallTracker_dump_and_reset::unused");
+    assert_eq!(A.hit(), 1);
+    assert_eq!(A.hit(), 2);
+    assert_eq!(B.hit(), 1);
+
+    let ours = |dump: Vec<(&'static str, u64)>| -> Vec<(&'static str, u64)> {
+        dump.into_iter().filter(|(label, _)| label.starts_with("CallTracker_dump_and_reset")).collect()
+    };
+    assert_eq!(ours(CallTracker::dump()), vec![(A.label(), 2), (B.label(), 1)]);
+    assert_eq!(UNUSED.count(), 0);
+
+    assert_eq!(A.reset(), 2);
+    assert_eq!(A.hit(), 1);
+    assert_eq!(ours(CallTracker::dump()), vec![(A.label(), 1), (B.label(), 1)]);
+}
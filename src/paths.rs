@@ -1,8 +1,117 @@
 //! Helper functions for iterating over files and folders
 
-use std::path::Path;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
 use walkdir::{WalkDir, DirEntry};
 
+
+/// Error type for the path helpers.
+#[derive(Debug)]
+pub enum PathError {
+    /// The given path doesn't exist.
+    NotFound(PathBuf),
+    /// The given path exists, but isn't a directory.
+    NotADirectory(PathBuf),
+    /// An IO error occurred while reading the file system.
+    Io(std::io::Error),
+}
+
+impl Display for PathError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PathError::NotFound(path) => write!(f, "Path does not exist: {}", path.display()),
+            PathError::NotADirectory(path) => write!(f, "Path is not a directory: {}", path.display()),
+            PathError::Io(e) => write!(f, "IO error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for PathError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PathError::Io(e) => Some(e),
+            _ => None
+        }
+    }
+}
+
+impl From<std::io::Error> for PathError {
+    fn from(e: std::io::Error) -> Self { PathError::Io(e) }
+}
+
+impl From<walkdir::Error> for PathError {
+    fn from(e: walkdir::Error) -> Self { PathError::Io(e.into()) }
+}
+
+/// A uniquely-named directory under `std::env::temp_dir()` that is deleted, along with everything
+/// in it, when the `TempDir` is dropped. Handy for tests that need to touch the file system.
+///
+/// ```
+/// # use toolbelt::paths::TempDir;
+/// let dir = TempDir::new().unwrap();
+/// std::fs::write(dir.path().join("scratch.txt"), "hello").unwrap();
+/// let path = dir.path().to_path_buf();
+/// drop(dir);
+/// assert!(!path.exists());
+/// ```
+#[derive(Debug)]
+pub struct TempDir {
+    path: PathBuf,
+}
+
+impl TempDir {
+    /// Creates a new, empty temporary directory.
+    pub fn new() -> std::io::Result<TempDir> {
+        static COUNTER: AtomicU64 = AtomicU64::new(0);
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.subsec_nanos()).unwrap_or(0);
+        loop {
+            let name = format!("toolbelt-tmp-{}-{}-{}", std::process::id(), nanos, COUNTER.fetch_add(1, Ordering::SeqCst));
+            let path = std::env::temp_dir().join(name);
+            match std::fs::create_dir(&path) {
+                Ok(()) => return Ok(TempDir { path }),
+                // left over from an earlier process with the same id, try the next name
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Returns the path of the directory.
+    pub fn path(&self) -> &Path { &self.path }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        // nothing useful to do if this fails, and panicking in drop is worse than leaving it behind
+        let _ = std::fs::remove_dir_all(&self.path);
+    }
+}
+
+/// Returns true if `path` exists and is a file (following symlinks).
+pub fn is_file(path: impl AsRef<Path>) -> bool {
+    path.as_ref().is_file()
+}
+
+/// Returns true if `path` exists and is a directory (following symlinks).
+pub fn is_dir(path: impl AsRef<Path>) -> bool {
+    path.as_ref().is_dir()
+}
+
+/// Makes sure a directory exists at `path`, creating it (and any missing parents) if needed.
+/// Returns `PathError::NotADirectory` if something other than a directory is already there.
+pub fn ensure_dir(path: impl AsRef<Path>) -> Result<(), PathError> {
+    let path = path.as_ref();
+    if path.is_dir() { return Ok(()) }
+    if path.exists() {
+        return Err(PathError::NotADirectory(path.to_path_buf()));
+    }
+    std::fs::create_dir_all(path)?;
+    Ok(())
+}
+
 pub fn entries_in_path(path: &str) -> Result<Vec<DirEntry>, String> {
     let path = Path::new(path);
     if !path.exists() {
@@ -58,3 +167,238 @@ pub fn directory_names_in_path(path: &str) -> Result<Vec<String>, String> {
         Err(e) => Err(e)
     }
 }
+
+// walks all entries under `path`, either just its direct children or recursively.
+// entries in each directory are sorted by name, and each directory comes before its contents
+fn walk_entries(path: &Path, recursive: bool) -> Result<Vec<DirEntry>, PathError> {
+    if !path.exists() {
+        return Err(PathError::NotFound(path.to_path_buf()));
+    }
+    let mut walker = WalkDir::new(path).min_depth(1).sort_by_file_name();
+    if !recursive {
+        walker = walker.max_depth(1);
+    }
+    let mut entries = Vec::new();
+    for entry in walker {
+        entries.push(entry?);
+    }
+    Ok(entries)
+}
+
+// walks all files (not directories) under `path`, either just its direct children or recursively
+fn walk_files(path: &Path, recursive: bool) -> Result<Vec<DirEntry>, PathError> {
+    Ok(walk_entries(path, recursive)?.into_iter().filter(|e| !e.file_type().is_dir()).collect())
+}
+
+/// Returns the paths of all files and directories in `root` (recursively, if `recursive` is
+/// true) relative to `root`, e.g. `nested/file.txt` rather than `/path/to/root/nested/file.txt`.
+/// Entries are sorted by name within each directory, with directories listed before their contents.
+pub fn relative_names_in_path(root: impl AsRef<Path>, recursive: bool) -> Result<Vec<PathBuf>, PathError> {
+    let root = root.as_ref();
+    Ok(walk_entries(root, recursive)?
+        .into_iter()
+        .map(|e| e.path().strip_prefix(root).expect("walked entries are always under the root").to_path_buf())
+        .collect())
+}
+
+/// The modification times of the files in a directory at some point, as returned by `snapshot_mtimes`.
+/// Remembers whether it was taken recursively, so `changed_since` walks the directory the same way.
+#[derive(Debug, Clone)]
+pub struct MtimeSnapshot {
+    recursive: bool,
+    mtimes: HashMap<PathBuf, SystemTime>,
+}
+
+impl MtimeSnapshot {
+    /// Whether the snapshot includes files in subdirectories.
+    pub fn is_recursive(&self) -> bool { self.recursive }
+    /// The number of files in the snapshot.
+    pub fn len(&self) -> usize { self.mtimes.len() }
+    /// Returns true if the snapshot contains no files.
+    pub fn is_empty(&self) -> bool { self.mtimes.is_empty() }
+    /// The recorded modification time of `path`, if it was in the snapshot.
+    pub fn get(&self, path: impl AsRef<Path>) -> Option<SystemTime> { self.mtimes.get(path.as_ref()).copied() }
+    /// Every file in the snapshot, along with its recorded modification time, in arbitrary order.
+    pub fn iter(&self) -> impl Iterator<Item = (&Path, SystemTime)> { self.mtimes.iter().map(|(p, t)| (p.as_path(), *t)) }
+    /// The recorded modification time of every file in the snapshot.
+    pub fn mtimes(&self) -> &HashMap<PathBuf, SystemTime> { &self.mtimes }
+}
+
+/// Records the modification time of every file in `path` (recursively, if `recursive` is true).
+/// Pass the result to `changed_since` later to find out which files have changed.
+pub fn snapshot_mtimes(path: impl AsRef<Path>, recursive: bool) -> Result<MtimeSnapshot, PathError> {
+    let mut mtimes = HashMap::new();
+    for entry in walk_files(path.as_ref(), recursive)? {
+        mtimes.insert(entry.path().to_path_buf(), entry.metadata()?.modified()?);
+    }
+    Ok(MtimeSnapshot { recursive, mtimes })
+}
+
+/// Returns all files in `path` that have a different modification time than in `snapshot`, or
+/// weren't present in it at all. `path` is walked recursively if the snapshot was.
+/// Files that have been deleted since the snapshot are not reported.
+pub fn changed_since(snapshot: &MtimeSnapshot, path: impl AsRef<Path>) -> Result<Vec<PathBuf>, PathError> {
+    let mut changed = Vec::new();
+    for entry in walk_files(path.as_ref(), snapshot.recursive)? {
+        let mtime = entry.metadata()?.modified()?;
+        if snapshot.get(entry.path()) != Some(mtime) {
+            changed.push(entry.path().to_path_buf());
+        }
+    }
+    Ok(changed)
+}
+
+/// Groups every file in `path` (recursively, if `recursive` is true) by its lowercased extension.
+/// Files without an extension are grouped under the empty string.
+pub fn files_by_extension(path: impl AsRef<Path>, recursive: bool) -> Result<HashMap<String, Vec<PathBuf>>, PathError> {
+    let mut groups: HashMap<String, Vec<PathBuf>> = HashMap::new();
+    for entry in walk_files(path.as_ref(), recursive)? {
+        let extension = entry.path().extension()
+            .map(|ext| ext.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        groups.entry(extension).or_default().push(entry.into_path());
+    }
+    Ok(groups)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::time::Duration;
+
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("toolbelt-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_changed_since() {
+        let dir = test_dir("changed-since");
+        fs::create_dir(dir.join("nested")).unwrap();
+        fs::write(dir.join("a.txt"), "a").unwrap();
+        fs::write(dir.join("b.txt"), "b").unwrap();
+        fs::write(dir.join("nested").join("c.txt"), "c").unwrap();
+
+        let snapshot = snapshot_mtimes(&dir, true).unwrap();
+        assert_eq!(snapshot.len(), 3);
+        assert!(snapshot.is_recursive());
+        let mut files: Vec<&Path> = snapshot.iter().map(|(path, _)| path).collect();
+        files.sort();
+        assert_eq!(files, vec![dir.join("a.txt"), dir.join("b.txt"), dir.join("nested").join("c.txt")]);
+        assert!(snapshot.iter().all(|(path, mtime)| snapshot.mtimes()[path] == mtime));
+        assert!(changed_since(&snapshot, &dir).unwrap().is_empty());
+
+        // "touch" a.txt by bumping its mtime, and add a new file
+        let file = fs::File::options().write(true).open(dir.join("a.txt")).unwrap();
+        file.set_modified(snapshot.get(dir.join("a.txt")).unwrap() + Duration::from_secs(10)).unwrap();
+        fs::write(dir.join("nested").join("d.txt"), "d").unwrap();
+
+        let mut changed = changed_since(&snapshot, &dir).unwrap();
+        changed.sort();
+        assert_eq!(changed, vec![dir.join("a.txt"), dir.join("nested").join("d.txt")]);
+        // a non-recursive snapshot only looks at the top level, so nested/d.txt isn't new to it
+        let shallow = snapshot_mtimes(&dir, false).unwrap();
+        assert_eq!(shallow.len(), 2);
+        assert!(!shallow.is_recursive());
+        fs::write(dir.join("nested").join("e.txt"), "e").unwrap();
+        assert!(changed_since(&shallow, &dir).unwrap().is_empty());
+        fs::write(dir.join("f.txt"), "f").unwrap();
+        assert_eq!(changed_since(&shallow, &dir).unwrap(), vec![dir.join("f.txt")]);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_temp_dir() {
+        let (a, b) = (TempDir::new().unwrap(), TempDir::new().unwrap());
+        assert_ne!(a.path(), b.path());
+        assert!(is_dir(a.path()));
+        assert!(a.path().starts_with(std::env::temp_dir()));
+        assert!(relative_names_in_path(a.path(), true).unwrap().is_empty());
+
+        fs::create_dir(a.path().join("nested")).unwrap();
+        fs::write(a.path().join("nested").join("file.txt"), "contents").unwrap();
+        let path = a.path().to_path_buf();
+        drop(a);
+        assert!(!path.exists());
+        assert!(is_dir(b.path()));
+    }
+
+    #[test]
+    fn test_predicates_and_ensure_dir() {
+        let dir = test_dir("ensure-dir");
+        fs::write(dir.join("file.txt"), "").unwrap();
+        assert!(is_dir(&dir));
+        assert!(!is_file(&dir));
+        assert!(is_file(dir.join("file.txt")));
+        assert!(!is_dir(dir.join("file.txt")));
+        assert!(!is_file(dir.join("missing")) && !is_dir(dir.join("missing")));
+
+        let nested = dir.join("a").join("b");
+        ensure_dir(&nested).unwrap();
+        assert!(is_dir(&nested));
+        // already existing is fine
+        ensure_dir(&nested).unwrap();
+
+        // a file in the way is an error, and is left alone
+        let result = ensure_dir(dir.join("file.txt"));
+        assert!(matches!(result, Err(PathError::NotADirectory(ref p)) if p == &dir.join("file.txt")));
+        assert!(is_file(dir.join("file.txt")));
+        assert!(matches!(ensure_dir(dir.join("file.txt").join("child")), Err(PathError::Io(_))));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_files_by_extension() {
+        let dir = test_dir("by-extension");
+        fs::write(dir.join("a.png"), "").unwrap();
+        fs::write(dir.join("b.PNG"), "").unwrap();
+        fs::write(dir.join("c.txt"), "").unwrap();
+        fs::write(dir.join("README"), "").unwrap();
+
+        let mut groups = files_by_extension(&dir, false).unwrap();
+        assert_eq!(groups.len(), 3);
+        groups.get_mut("png").unwrap().sort();
+        assert_eq!(groups["png"], vec![dir.join("a.png"), dir.join("b.PNG")]);
+        assert_eq!(groups["txt"], vec![dir.join("c.txt")]);
+        assert_eq!(groups[""], vec![dir.join("README")]);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_relative_names_in_path() {
+        let dir = test_dir("relative-names");
+        fs::create_dir_all(dir.join("nested").join("deeper")).unwrap();
+        fs::write(dir.join("a.txt"), "").unwrap();
+        fs::write(dir.join("nested").join("b.txt"), "").unwrap();
+        fs::write(dir.join("nested").join("deeper").join("c.txt"), "").unwrap();
+
+        let names = relative_names_in_path(&dir, true).unwrap();
+        let expected: Vec<PathBuf> = vec![
+            PathBuf::from("a.txt"),
+            PathBuf::from("nested"),
+            Path::new("nested").join("b.txt"),
+            Path::new("nested").join("deeper"),
+            Path::new("nested").join("deeper").join("c.txt"),
+        ];
+        assert_eq!(names, expected);
+        assert!(names.iter().all(|p| p.is_relative()));
+        assert_eq!(names[4].components().count(), 3);
+
+        let shallow = relative_names_in_path(&dir, false).unwrap();
+        assert_eq!(shallow, vec![PathBuf::from("a.txt"), PathBuf::from("nested")]);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_snapshot_missing_path() {
+        let result = snapshot_mtimes("/this/path/does/not/exist", false);
+        assert!(matches!(result, Err(PathError::NotFound(_))));
+    }
+}
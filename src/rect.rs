@@ -10,16 +10,69 @@ pub struct Rect<N: Num + NumCast + Copy + PartialOrd> {
     pub h: N,
 }
 
+/// A point on a rect to position things relative to, with `y` increasing downwards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Anchor {
+    TopLeft, Top, TopRight,
+    Left, Center, Right,
+    BottomLeft, Bottom, BottomRight,
+}
+
+impl Anchor {
+    // position along each axis: 0 = start (left/top), 1 = middle, 2 = end (right/bottom)
+    fn axes(self) -> (u8, u8) {
+        match self {
+            Anchor::TopLeft => (0, 0),
+            Anchor::Top => (1, 0),
+            Anchor::TopRight => (2, 0),
+            Anchor::Left => (0, 1),
+            Anchor::Center => (1, 1),
+            Anchor::Right => (2, 1),
+            Anchor::BottomLeft => (0, 2),
+            Anchor::Bottom => (1, 2),
+            Anchor::BottomRight => (2, 2),
+        }
+    }
+}
+
 #[inline]
 fn _cast<N: NumCast>(value: f32) -> N {
     num::cast::<f32, N>(value).unwrap()
 }
 
+#[inline]
+fn _to_f32<N: NumCast>(value: N) -> f32 {
+    num::cast::<N, f32>(value).unwrap()
+}
+
+#[inline]
+fn _is_integer<N: Num + PartialOrd>() -> bool {
+    N::one() / (N::one() + N::one()) == N::zero()
+}
+
 impl<N: Num + NumCast + Copy + PartialOrd> Rect<N> {
     pub fn test(&self, pos_x: N, pos_y: N) -> bool {
         pos_x >= self.x && pos_x <= self.x + self.w && pos_y >= self.y && pos_y <= self.y + self.h
     }
 
+    /// Like `test`, but treats the rect as having rounded corners with the given `radius`, so points
+    /// in the corners outside the arcs aren't considered inside. The radius is clamped to half the
+    /// shorter side.
+    pub fn test_rounded(&self, pos_x: N, pos_y: N, radius: N) -> bool {
+        if !self.test(pos_x, pos_y) { return false }
+        let (x, y, w, h) = (_to_f32(self.x), _to_f32(self.y), _to_f32(self.w), _to_f32(self.h));
+        let radius = _to_f32(radius).clamp(0.0, w.min(h) * 0.5);
+        // distance from the point to the rect shrunk by `radius`, which is only nonzero in the corners
+        // rounding can push the far edge of the inner box past the near one when the radius is
+        // half the side, which would make `clamp` panic
+        let (lo_x, lo_y) = (x + radius, y + radius);
+        let (hi_x, hi_y) = ((x + w - radius).max(lo_x), (y + h - radius).max(lo_y));
+        let (pos_x, pos_y) = (_to_f32(pos_x), _to_f32(pos_y));
+        let dx = pos_x - pos_x.clamp(lo_x, hi_x);
+        let dy = pos_y - pos_y.clamp(lo_y, hi_y);
+        dx * dx + dy * dy <= radius * radius
+    }
+
     pub fn adjusted_by(&self, dx: N, dy: N, dw: N, dh: N) -> Rect<N> {
         Rect {
             x: self.x + dx,
@@ -37,6 +90,142 @@ impl<N: Num + NumCast + Copy + PartialOrd> Rect<N> {
         self.adjusted_by(expand_x * _cast(-1.0), expand_x * _cast(-1.0), expand_y * _cast(2.0), expand_y * _cast(2.0))
     }
 
+    /// Multiplies `x`, `y`, `w`, and `h` by `factor`, e.g. to convert a rect in logical units to
+    /// physical pixels on a hi-DPI display. Unlike scaling just the size, this also moves the
+    /// origin. Results are truncated towards zero for integer types. A factor that isn't finite
+    /// leaves the rect unchanged.
+    ///
+    /// # Panics
+    /// For integer types, panics if a scaled value doesn't fit in `N`.
+    pub fn scaled_by(&self, factor: f32) -> Rect<N> {
+        if !factor.is_finite() { return *self }
+        let scale = |value: N| -> N { _cast(_to_f32(value) * factor) };
+        Rect {
+            x: scale(self.x),
+            y: scale(self.y),
+            w: scale(self.w),
+            h: scale(self.h)
+        }
+    }
+
+    /// The inverse of `scaled_by`: divides `x`, `y`, `w`, and `h` by `factor`, e.g. to convert
+    /// physical pixels back to logical units. A zero or non-finite factor leaves the rect unchanged.
+    ///
+    /// # Panics
+    /// For integer types, panics if a scaled value doesn't fit in `N`.
+    pub fn unscaled_by(&self, factor: f32) -> Rect<N> {
+        if factor == 0.0 || !factor.is_finite() { return *self }
+        self.scaled_by(1.0 / factor)
+    }
+
+    /// Rounds `x`, `y`, `w`, and `h` to the nearest multiple of `grid`, with halfway values rounded
+    /// away from zero. Integer types are snapped with integer arithmetic, so they stay exact. A grid
+    /// of zero or less leaves the rect unchanged.
+    ///
+    /// # Panics
+    /// Panics if a snapped value doesn't fit in `N`.
+    pub fn snap_to_grid(&self, grid: N) -> Rect<N> {
+        if grid <= N::zero() { return *self }
+        let snap = |value: N| -> N {
+            if !_is_integer::<N>() {
+                let grid = _to_f32(grid);
+                return _cast((_to_f32(value) / grid).round() * grid);
+            }
+            // the remainder has the sign of `value`, so step away from or back towards zero
+            let rem = value % grid;
+            if value >= N::zero() {
+                if rem >= grid - rem { value + (grid - rem) } else { value - rem }
+            }
+            else {
+                let rem = N::zero() - rem;
+                if rem >= grid - rem { value - (grid - rem) } else { value + rem }
+            }
+        };
+        Rect {
+            x: snap(self.x),
+            y: snap(self.y),
+            w: snap(self.w),
+            h: snap(self.h)
+        }
+    }
+
+    /// Rounds `x`, `y`, `w`, and `h` to whole pixels. Equivalent to `snap_to_grid(1)`, and the
+    /// identity for integer types.
+    pub fn pixel_snap(&self) -> Rect<N> {
+        if _is_integer::<N>() { return *self }
+        self.snap_to_grid(N::one())
+    }
+
+    /// Splits the rect into four quadrants, returned in the order top-left, top-right, bottom-left,
+    /// bottom-right (with `y` increasing downwards). For integer types with odd sizes, the right
+    /// and bottom quadrants get the extra unit so the children still tile the parent exactly.
+    pub fn subdivide(&self) -> [Rect<N>; 4] {
+        let (left_w, top_h) = (self.w / _cast(2.0), self.h / _cast(2.0));
+        let (right_w, bottom_h) = (self.w - left_w, self.h - top_h);
+        let (mid_x, mid_y) = (self.x + left_w, self.y + top_h);
+        [
+            Rect { x: self.x, y: self.y, w: left_w, h: top_h },
+            Rect { x: mid_x, y: self.y, w: right_w, h: top_h },
+            Rect { x: self.x, y: mid_y, w: left_w, h: bottom_h },
+            Rect { x: mid_x, y: mid_y, w: right_w, h: bottom_h },
+        ]
+    }
+
+    /// Splits the rect into a grid of `cols` by `rows` equally-sized cells, yielded left-to-right then
+    /// top-to-bottom. Yields nothing if either `cols` or `rows` is zero. For integer types, cell
+    /// edges are rounded down, so cells may differ in size by one unit but still tile the parent exactly.
+    pub fn grid(&self, cols: usize, rows: usize) -> impl Iterator<Item = Rect<N>> {
+        let rect = *self;
+        let (w, h) = (_to_f32(self.w), _to_f32(self.h));
+        let col_edge = move |c: usize| -> N { rect.x + _cast(w * c as f32 / cols as f32) };
+        let row_edge = move |r: usize| -> N { rect.y + _cast(h * r as f32 / rows as f32) };
+        (0..rows).flat_map(move |r| (0..cols).map(move |c| {
+            let (x, y) = (col_edge(c), row_edge(r));
+            Rect { x, y, w: col_edge(c + 1) - x, h: row_edge(r + 1) - y }
+        }))
+    }
+
+    /// Returns the area of the overlap between this rect and `other`, or zero if they don't overlap.
+    pub fn intersection_area(&self, other: &Rect<N>) -> N {
+        let max = |a: N, b: N| if a > b { a } else { b };
+        let min = |a: N, b: N| if a < b { a } else { b };
+        let (start_x, end_x) = (max(self.x, other.x), min(self.x + self.w, other.x + other.w));
+        let (start_y, end_y) = (max(self.y, other.y), min(self.y + self.h, other.y + other.h));
+        // compare before subtracting so unsigned types can't underflow
+        if end_x <= start_x || end_y <= start_y { return N::zero() }
+        (end_x - start_x) * (end_y - start_y)
+    }
+
+    /// Returns the intersection-over-union of this rect and `other`: the area of their overlap
+    /// divided by the area covered by either of them. Ranges from 0.0 for disjoint rects to 1.0
+    /// for identical ones. Returns 0.0 if both rects have zero area.
+    pub fn iou(&self, other: &Rect<N>) -> f32 {
+        let intersection = _to_f32(self.intersection_area(other));
+        let union = _to_f32(self.w * self.h) + _to_f32(other.w * other.h) - intersection;
+        if union <= 0.0 { 0.0 } else { intersection / union }
+    }
+
+    /// Returns a rect with the same size as this one, positioned inside `parent` at `anchor`, e.g.
+    /// `BottomRight` puts this rect's bottom-right corner on the parent's. Centered positions are
+    /// rounded down for integer types. For unsigned types, this rect must not be larger than
+    /// `parent` unless it's anchored at the top left.
+    pub fn anchored_in(&self, parent: &Rect<N>, anchor: Anchor) -> Rect<N> {
+        let place = |axis: u8, start: N, parent_size: N, size: N| -> N {
+            match axis {
+                0 => start,
+                1 => start + (parent_size - size) / _cast(2.0),
+                _ => start + parent_size - size,
+            }
+        };
+        let (axis_x, axis_y) = anchor.axes();
+        Rect {
+            x: place(axis_x, parent.x, parent.w, self.w),
+            y: place(axis_y, parent.y, parent.h, self.h),
+            w: self.w,
+            h: self.h,
+        }
+    }
+
     pub fn position(&self) -> cgmath::Point2<N> {
         cgmath::Point2::new(self.x, self.y)
     }
@@ -45,3 +234,243 @@ impl<N: Num + NumCast + Copy + PartialOrd> Rect<N> {
         cgmath::Vector2::new(self.x, self.y)
     }
 }
+
+
+/// Packs items of the given `(width, height)` into `bin` using a simple shelf algorithm, e.g. for
+/// building a texture atlas. Items are placed left-to-right in the order given, starting a new
+/// shelf below the tallest item of the current one when the row is full. Returns the placed rect
+/// for each item, or `None` for items that don't fit.
+pub fn pack_rects(bin: Rect<i32>, items: &[(u32, u32)]) -> Vec<Option<Rect<i32>>> {
+    let (mut cursor_x, mut shelf_y, mut shelf_h) = (0i32, 0i32, 0i32);
+    // checked so huge items are rejected instead of overflowing
+    let exceeds = |pos: i32, len: i32, limit: i32| pos.checked_add(len).is_none_or(|end| end > limit);
+    items.iter().map(|&(w, h)| {
+        let (w, h) = (i32::try_from(w).ok()?, i32::try_from(h).ok()?);
+        let (mut x, mut y, mut row_h) = (cursor_x, shelf_y, shelf_h);
+        if exceeds(x, w, bin.w) {
+            // start a new shelf
+            x = 0;
+            y += row_h;
+            row_h = 0;
+        }
+        if exceeds(x, w, bin.w) || exceeds(y, h, bin.h) {
+            return None;
+        }
+        cursor_x = x + w;
+        shelf_y = y;
+        shelf_h = row_h.max(h);
+        Some(Rect { x: bin.x + x, y: bin.y + y, w, h })
+    }).collect()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::{pack_rects, Anchor, Rect};
+
+    #[test]
+    fn test_snap_to_grid() {
+        let r = Rect { x: 1.3f32, y: 2.6, w: 10.2, h: 4.9 };
+        let snapped = r.pixel_snap();
+        assert_eq!((snapped.x, snapped.y, snapped.w, snapped.h), (1.0, 3.0, 10.0, 5.0));
+        let snapped = r.snap_to_grid(0.5);
+        assert_eq!((snapped.x, snapped.y, snapped.w, snapped.h), (1.5, 2.5, 10.0, 5.0));
+    }
+
+    #[test]
+    fn test_scaled_by() {
+        let logical = Rect { x: 10.0f32, y: -4.0, w: 100.0, h: 50.5 };
+        let physical = logical.scaled_by(2.0);
+        assert_eq!((physical.x, physical.y, physical.w, physical.h), (20.0, -8.0, 200.0, 101.0));
+        let back = physical.unscaled_by(2.0);
+        assert_eq!((back.x, back.y, back.w, back.h), (logical.x, logical.y, logical.w, logical.h));
+
+        let pixels = Rect { x: 3, y: 5, w: 7, h: 9 }.scaled_by(1.5);
+        assert_eq!((pixels.x, pixels.y, pixels.w, pixels.h), (4, 7, 10, 13));
+
+        // degenerate factors leave integer rects alone instead of panicking on the cast
+        let r = Rect { x: 3, y: 5, w: 7, h: 9 };
+        for factor in [f32::NAN, f32::INFINITY, f32::NEG_INFINITY] {
+            let scaled = r.scaled_by(factor);
+            assert_eq!((scaled.x, scaled.y, scaled.w, scaled.h), (3, 5, 7, 9));
+        }
+        for factor in [0.0, -0.0, f32::NAN, f32::INFINITY] {
+            let unscaled = r.unscaled_by(factor);
+            assert_eq!((unscaled.x, unscaled.y, unscaled.w, unscaled.h), (3, 5, 7, 9));
+        }
+        let zero = r.scaled_by(0.0);
+        assert_eq!((zero.x, zero.y, zero.w, zero.h), (0, 0, 0, 0));
+    }
+
+    #[test]
+    fn test_subdivide() {
+        let parent = Rect { x: 2.0f32, y: 4.0, w: 8.0, h: 6.0 };
+        let [tl, tr, bl, br] = parent.subdivide();
+        for child in [tl, tr, bl, br] {
+            assert_eq!(child.w * child.h, parent.w * parent.h / 4.0);
+        }
+        assert_eq!((tl.x, tl.y), (2.0, 4.0));
+        assert_eq!((tr.x, tr.y), (6.0, 4.0));
+        assert_eq!((bl.x, bl.y), (2.0, 7.0));
+        assert_eq!((br.x, br.y), (6.0, 7.0));
+        assert_eq!((br.x + br.w, br.y + br.h), (parent.x + parent.w, parent.y + parent.h));
+
+        // odd integer sizes still tile exactly
+        let [tl, _, _, br] = Rect { x: 0, y: 0, w: 5, h: 3 }.subdivide();
+        assert_eq!((tl.w, tl.h), (2, 1));
+        assert_eq!((br.x, br.y, br.w, br.h), (2, 1, 3, 2));
+    }
+
+    #[test]
+    fn test_grid() {
+        let parent = Rect { x: 2.0f32, y: 4.0, w: 8.0, h: 6.0 };
+        let cells: Vec<_> = parent.grid(2, 2).map(|r| (r.x, r.y, r.w, r.h)).collect();
+        assert_eq!(cells, vec![(2.0, 4.0, 4.0, 3.0), (6.0, 4.0, 4.0, 3.0), (2.0, 7.0, 4.0, 3.0), (6.0, 7.0, 4.0, 3.0)]);
+        assert_eq!(parent.grid(0, 3).count(), 0);
+        assert_eq!(parent.grid(3, 0).count(), 0);
+
+        // integer cells tile exactly even when the size doesn't divide evenly
+        let cells: Vec<_> = Rect { x: 0, y: 0, w: 10, h: 1 }.grid(3, 1).map(|r| (r.x, r.w)).collect();
+        assert_eq!(cells, vec![(0, 3), (3, 3), (6, 4)]);
+    }
+
+    #[test]
+    fn test_anchored_in() {
+        let parent = Rect { x: 10.0f32, y: 20.0, w: 100.0, h: 50.0 };
+        let child = Rect { x: -3.0f32, y: 7.0, w: 20.0, h: 10.0 };
+        let at = |anchor| {
+            let r = child.anchored_in(&parent, anchor);
+            assert_eq!((r.w, r.h), (20.0, 10.0));
+            (r.x, r.y)
+        };
+        assert_eq!(at(Anchor::Center), (50.0, 40.0));
+        assert_eq!(at(Anchor::TopLeft), (10.0, 20.0));
+        assert_eq!(at(Anchor::TopRight), (90.0, 20.0));
+        assert_eq!(at(Anchor::BottomLeft), (10.0, 60.0));
+        assert_eq!(at(Anchor::BottomRight), (90.0, 60.0));
+        assert_eq!(at(Anchor::Top), (50.0, 20.0));
+        assert_eq!(at(Anchor::Left), (10.0, 40.0));
+        assert_eq!(at(Anchor::Right), (90.0, 40.0));
+        assert_eq!(at(Anchor::Bottom), (50.0, 60.0));
+
+        // a larger child overhangs the parent evenly
+        let big = Rect { x: 0, y: 0, w: 12, h: 4 }.anchored_in(&Rect { x: 0, y: 0, w: 8, h: 8 }, Anchor::Center);
+        assert_eq!((big.x, big.y), (-2, 2));
+        let odd = Rect { x: 0u32, y: 0, w: 3, h: 3 }.anchored_in(&Rect { x: 0, y: 0, w: 8, h: 8 }, Anchor::Center);
+        assert_eq!((odd.x, odd.y), (2, 2));
+    }
+
+    #[test]
+    fn test_intersection_area_and_iou() {
+        let a = Rect { x: 0, y: 0, w: 4, h: 2 };
+        assert_eq!(a.intersection_area(&a), 8);
+        assert_eq!(a.iou(&a), 1.0);
+
+        let disjoint = Rect { x: 10, y: 0, w: 4, h: 2 };
+        assert_eq!(a.intersection_area(&disjoint), 0);
+        assert_eq!(a.iou(&disjoint), 0.0);
+        // touching edges don't count as overlapping
+        assert_eq!(a.intersection_area(&Rect { x: 4, y: 0, w: 4, h: 2 }), 0);
+
+        // half of each rect overlaps: 4 / (8 + 8 - 4)
+        let half = Rect { x: 2, y: 0, w: 4, h: 2 };
+        assert_eq!(a.intersection_area(&half), 4);
+        assert!((a.iou(&half) - 1.0 / 3.0).abs() < 0.0001);
+
+        let empty = Rect { x: 1.0f32, y: 1.0, w: 0.0, h: 0.0 };
+        assert_eq!(empty.iou(&empty), 0.0);
+
+        // disjoint unsigned rects don't underflow
+        let a = Rect { x: 0u32, y: 0, w: 4, h: 2 };
+        let b = Rect { x: 10u32, y: 0, w: 4, h: 2 };
+        assert_eq!(a.intersection_area(&b), 0);
+        assert_eq!(b.intersection_area(&a), 0);
+        assert_eq!(a.iou(&b), 0.0);
+        assert_eq!(a.intersection_area(&Rect { x: 2, y: 1, w: 4, h: 4 }), 2);
+    }
+
+    #[test]
+    fn test_snap_to_grid_integer() {
+        let r = Rect { x: 3i32, y: 7, w: 12, h: 1 };
+        let snapped = r.pixel_snap();
+        assert_eq!((snapped.x, snapped.y, snapped.w, snapped.h), (3, 7, 12, 1));
+        let snapped = r.snap_to_grid(4);
+        assert_eq!((snapped.x, snapped.y, snapped.w, snapped.h), (4, 8, 12, 0));
+        // a grid of zero would divide by zero and panic on the cast back to i32
+        for grid in [0, -4] {
+            let snapped = r.snap_to_grid(grid);
+            assert_eq!((snapped.x, snapped.y, snapped.w, snapped.h), (3, 7, 12, 1));
+        }
+        // negative values round to the nearest multiple too, with ties away from zero
+        let snapped = Rect { x: -3i32, y: -1, w: -2, h: -6 }.snap_to_grid(4);
+        assert_eq!((snapped.x, snapped.y, snapped.w, snapped.h), (-4, 0, -4, -8));
+
+        // integers never go through f32, so large values stay exact
+        let big = Rect { x: 16_777_217i32, y: i32::MIN, w: i32::MAX, h: 0 };
+        let snapped = big.pixel_snap();
+        assert_eq!((snapped.x, snapped.y, snapped.w, snapped.h), (16_777_217, i32::MIN, i32::MAX, 0));
+        assert_eq!(Rect { x: 16_777_217i32, y: 0, w: 0, h: 0 }.snap_to_grid(2).x, 16_777_218);
+        let huge = Rect { x: u64::MAX, y: u64::MAX - 1, w: 1, h: 0 };
+        let snapped = huge.pixel_snap();
+        assert_eq!((snapped.x, snapped.y, snapped.w, snapped.h), (u64::MAX, u64::MAX - 1, 1, 0));
+        assert_eq!(Rect { x: 0u64, y: u64::MAX - 1, w: 1, h: 0 }.snap_to_grid(2).y, u64::MAX - 1);
+    }
+
+    #[test]
+    fn test_test_rounded() {
+        let r = Rect { x: 0.0f32, y: 0.0, w: 10.0, h: 6.0 };
+        assert!(r.test_rounded(5.0, 3.0, 2.0));
+        // corners are clipped, even though the plain test passes
+        assert!(r.test(0.2, 0.2));
+        assert!(!r.test_rounded(0.2, 0.2, 2.0));
+        assert!(!r.test_rounded(9.8, 5.8, 2.0));
+        // straight edges are unaffected
+        assert!(r.test_rounded(5.0, 0.0, 2.0));
+        assert!(r.test_rounded(10.0, 3.0, 2.0));
+        assert!(!r.test_rounded(11.0, 3.0, 2.0));
+
+        let r = Rect { x: 0, y: 0, w: 10, h: 10 };
+        assert!(!r.test_rounded(0, 0, 3));
+        assert!(r.test_rounded(1, 1, 3));
+        assert!(r.test_rounded(0, 0, 0));
+
+        // a pill shape, where `x + radius` rounds to just past `x + w - radius`
+        let pill = Rect { x: 41.145435f32, y: 0.0, w: 31.994986, h: 64.0 };
+        assert!(pill.test_rounded(57.0, 32.0, 100.0));
+        assert!(pill.test_rounded(57.0, 32.0, pill.w / 2.0));
+        assert!(!pill.test_rounded(41.2, 0.5, pill.w / 2.0));
+    }
+
+    #[test]
+    fn test_pack_rects_exact_fit() {
+        let bin = Rect { x: 10, y: 20, w: 4, h: 4 };
+        let packed = pack_rects(bin, &[(2, 2), (2, 2), (4, 1), (1, 1), (3, 1)]);
+        let placed: Vec<(i32, i32, i32, i32)> = packed.iter().map(|r| {
+            let r = r.unwrap();
+            (r.x, r.y, r.w, r.h)
+        }).collect();
+        assert_eq!(placed, vec![(10, 20, 2, 2), (12, 20, 2, 2), (10, 22, 4, 1), (10, 23, 1, 1), (11, 23, 3, 1)]);
+    }
+
+    #[test]
+    fn test_pack_rects_oversized() {
+        let bin = Rect { x: 0, y: 0, w: 4, h: 4 };
+        let packed = pack_rects(bin, &[(5, 1), (2, 2), (1, 5), (4, 2), (4, 1)]);
+        assert!(packed[0].is_none());
+        assert!(packed[1].is_some());
+        assert!(packed[2].is_none());
+        assert!(packed[3].is_some());
+        assert!(packed[4].is_none());
+    }
+
+    #[test]
+    fn test_pack_rects_huge_after_placed() {
+        let bin = Rect { x: 0, y: 0, w: 10, h: 10 };
+        let packed = pack_rects(bin, &[(5, 5), (i32::MAX as u32, 1), (1, i32::MAX as u32), (5, 5)]);
+        let placed = |r: Option<Rect<i32>>| r.map(|r| (r.x, r.y, r.w, r.h));
+        assert_eq!(placed(packed[0]), Some((0, 0, 5, 5)));
+        assert!(packed[1].is_none());
+        assert!(packed[2].is_none());
+        assert_eq!(placed(packed[3]), Some((5, 0, 5, 5)));
+    }
+}
@@ -0,0 +1,81 @@
+//! Helpers for testing color math. Only available with the `test-util` feature.
+
+use crate::{Color, ColorSpace};
+
+
+/// Asserts that two colors are approximately equal. The second color is converted into the first
+/// color's space before comparing, and every component (including alpha) must be within `eps`.
+///
+/// ```
+/// # use toolbelt::{assert_color_approx_eq, Color};
+/// let red = Color::from_rgb(1.0, 0.0, 0.0);
+/// assert_color_approx_eq!(red, Color::from_hsv(0.0, 1.0, 1.0), 0.0001);
+/// ```
+#[macro_export]
+macro_rules! assert_color_approx_eq {
+    ($a:expr, $b:expr, $eps:expr) => {{
+        let a: $crate::Color = $a;
+        let mut b: $crate::Color = $b;
+        let eps: f32 = $eps;
+        b.convert(a.space());
+        let diff = $crate::test_util::max_component_difference(&a, &b);
+        assert!(diff <= eps, "colors differ by {} (more than {}):\n  left: {:?}\n right: {:?}", diff, eps, a, b);
+    }};
+}
+
+/// Converts `color` into `space` and back again, and returns the largest difference between any
+/// component of the original and the round-tripped color. Useful for checking conversion accuracy.
+pub fn roundtrip_error(color: Color, space: ColorSpace) -> f32 {
+    let mut converted = color;
+    converted.convert(space);
+    converted.convert(color.space());
+    max_component_difference(&color, &converted)
+}
+
+/// Returns the largest absolute difference between any two corresponding components (including
+/// alpha) of two colors, without converting either of them.
+pub fn max_component_difference(a: &Color, b: &Color) -> f32 {
+    a.components_4().iter().zip(b.components_4().iter())
+        .map(|(x, y)| (x - y).abs())
+        .fold(0.0, f32::max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::roundtrip_error;
+    use crate::{Color, ColorSpace};
+
+    #[test]
+    fn test_roundtrip_error_rgb_hsv() {
+        for color in [
+            Color::from_rgb(1.0, 0.0, 0.0),
+            Color::from_rgb(0.2, 0.7, 0.4),
+            Color::from_rgba(0.9, 0.1, 0.6, 0.5),
+            Color::from_rgb(0.5, 0.5, 0.5),
+        ] {
+            assert!(roundtrip_error(color, ColorSpace::HSV) < 0.0001);
+        }
+    }
+
+    #[test]
+    fn test_roundtrip_error_rgb_hsl() {
+        for color in [
+            Color::from_rgb(1.0, 0.0, 0.0),
+            Color::from_rgb(0.2, 0.7, 0.4),
+            Color::from_rgba(0.9, 0.1, 0.6, 0.5),
+        ] {
+            assert!(roundtrip_error(color, ColorSpace::HSL) < 0.0001);
+        }
+    }
+
+    #[test]
+    fn test_assert_color_approx_eq() {
+        assert_color_approx_eq!(Color::from_rgb(0.0, 1.0, 1.0), Color::from_hsv(0.5, 1.0, 1.0), 0.0001);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_assert_color_approx_eq_fails() {
+        assert_color_approx_eq!(Color::from_rgb(0.0, 1.0, 1.0), Color::from_rgb(0.0, 1.0, 0.9), 0.01);
+    }
+}
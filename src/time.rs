@@ -0,0 +1,103 @@
+//! Simple timing utilities.
+
+use std::time::{Duration, Instant};
+
+
+/// A simple stopwatch for measuring elapsed time, with support for recording laps.
+///
+/// ```
+/// # use toolbelt::time::Stopwatch;
+/// let mut stopwatch = Stopwatch::start();
+/// // ... do some work ...
+/// let first = stopwatch.lap();
+/// // ... do some more work ...
+/// let second = stopwatch.lap();
+/// assert_eq!(stopwatch.laps(), &[first, second]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Stopwatch {
+    start: Instant,
+    last_lap: Instant,
+    laps: Vec<Duration>,
+}
+
+impl Stopwatch {
+    /// Creates a new stopwatch which starts timing immediately.
+    pub fn start() -> Self {
+        let now = Instant::now();
+        Stopwatch { start: now, last_lap: now, laps: Vec::new() }
+    }
+
+    /// Returns the time elapsed since the stopwatch was started or last restarted.
+    pub fn elapsed(&self) -> Duration { self.start.elapsed() }
+
+    /// Restarts the stopwatch and clears all recorded laps.
+    /// Returns the time elapsed before the restart.
+    pub fn restart(&mut self) -> Duration {
+        let now = Instant::now();
+        let elapsed = now - self.start;
+        self.start = now;
+        self.last_lap = now;
+        self.laps.clear();
+        elapsed
+    }
+
+    /// Records a lap and returns the time elapsed since the previous lap (or since starting,
+    /// for the first lap).
+    pub fn lap(&mut self) -> Duration {
+        let now = Instant::now();
+        let lap = now - self.last_lap;
+        self.last_lap = now;
+        self.laps.push(lap);
+        lap
+    }
+
+    /// Returns all laps recorded since the stopwatch was started or last restarted.
+    pub fn laps(&self) -> &[Duration] { &self.laps }
+}
+
+impl Default for Stopwatch {
+    fn default() -> Self { Stopwatch::start() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Stopwatch;
+    use std::thread::sleep;
+    use std::time::Duration;
+
+    #[test]
+    fn test_stopwatch_elapsed_is_monotonic() {
+        let stopwatch = Stopwatch::start();
+        sleep(Duration::from_millis(5));
+        let first = stopwatch.elapsed();
+        sleep(Duration::from_millis(5));
+        let second = stopwatch.elapsed();
+        assert!(first >= Duration::from_millis(5));
+        assert!(second > first);
+    }
+
+    #[test]
+    fn test_stopwatch_laps() {
+        let mut stopwatch = Stopwatch::start();
+        sleep(Duration::from_millis(5));
+        let first = stopwatch.lap();
+        sleep(Duration::from_millis(5));
+        let second = stopwatch.lap();
+        assert!(first >= Duration::from_millis(5));
+        assert!(second >= Duration::from_millis(5));
+        assert_eq!(stopwatch.laps(), &[first, second]);
+        assert!(stopwatch.elapsed() >= first + second);
+    }
+
+    #[test]
+    fn test_stopwatch_restart() {
+        let mut stopwatch = Stopwatch::start();
+        sleep(Duration::from_millis(5));
+        stopwatch.lap();
+        let elapsed = stopwatch.restart();
+        assert!(elapsed >= Duration::from_millis(5));
+        assert!(stopwatch.laps().is_empty());
+        assert!(stopwatch.elapsed() < elapsed);
+    }
+}
@@ -1,14 +1,24 @@
-//! 3D transform with position, rotation, and scale.
+//! 2D and 3D transforms with position, rotation, and scale.
 
-use cgmath::{Point3, Quaternion, Vector3, Matrix4, EuclideanSpace};
+use std::cell::Cell;
+use std::fmt::{Debug, Formatter};
+use cgmath::{Point2, Point3, Quaternion, Vector2, Vector3, Matrix3, Matrix4, Basis2, Rad, EuclideanSpace, ElementWise, InnerSpace, One, Rotation, Rotation2, Rotation3, SquareMatrix, Transform as CgTransform};
+use crate::aabb::AABB;
+use crate::rect::Rect;
 
 
 /// A 3D transform, with position, rotation, and scale.
-#[derive(Clone, Debug)]
+#[derive(Clone)]
 pub struct Transform {
     pub position: Point3<f32>,
     pub rotation: Quaternion<f32>,
-    pub scale: Vector3<f32>
+    pub scale: Vector3<f32>,
+}
+
+#[cfg(test)]
+thread_local! {
+    // counts calls to `Transform::to_matrix` so tests can check `CachedTransform`'s cache is being used
+    static MATRIX_COMPUTATIONS: Cell<usize> = const { Cell::new(0) };
 }
 
 
@@ -18,7 +28,7 @@ impl Transform {
     pub fn identity() -> Transform {
         Transform {
             position: Point3::new(0.0, 0.0, 0.0),
-            rotation: Quaternion::from_sv(0.0, Vector3::new(0.0, 0.0, 0.0)),
+            rotation: Quaternion::one(),
             scale: Vector3::new(1.0, 1.0, 1.0),
         }
     }
@@ -27,7 +37,7 @@ impl Transform {
     pub fn from_position(position: Point3<f32>) -> Transform {
         Transform {
             position,
-            rotation: Quaternion::from_sv(0.0, Vector3::new(0.0, 0.0, 0.0)),
+            rotation: Quaternion::one(),
             scale: Vector3::new(1.0, 1.0, 1.0),
         }
     }
@@ -45,8 +55,8 @@ impl Transform {
     pub fn from_scale(scale: Vector3<f32>) -> Transform {
         Transform {
             position: Point3::new(0.0, 0.0, 0.0),
-            rotation: Quaternion::from_sv(0.0, Vector3::new(0.0, 0.0, 0.0)),
-            scale
+            rotation: Quaternion::one(),
+            scale,
         }
     }
 
@@ -54,15 +64,442 @@ impl Transform {
     pub fn from_uniform_scale(scale: f32) -> Transform {
         Transform {
             position: Point3::new(0.0, 0.0, 0.0),
-            rotation: Quaternion::from_sv(0.0, Vector3::new(0.0, 0.0, 0.0)),
+            rotation: Quaternion::one(),
             scale: Vector3::new(scale, scale, scale),
         }
     }
 
+    /// Creates a transform from the given translation, rotation, and scale.
+    pub fn from_trs(translation: Vector3<f32>, rotation: Quaternion<f32>, scale: Vector3<f32>) -> Transform {
+        Transform {
+            position: Point3::from_vec(translation),
+            rotation,
+            scale,
+        }
+    }
+
+    /// Creates a rotation-only transform rotating by `angle` around `axis`. The axis doesn't need
+    /// to be normalized.
+    pub fn from_axis_angle<A: Into<Rad<f32>>>(axis: Vector3<f32>, angle: A) -> Transform {
+        Transform::from_rotation(Quaternion::from_axis_angle(axis.normalize(), angle))
+    }
+
+    /// Creates a rotation-only transform from Euler angles: `pitch` around the x axis, `yaw` around
+    /// the y axis, and `roll` around the z axis. The rotations are applied roll first, then pitch,
+    /// then yaw, matching the yaw-then-pitch matrices used by `view_to_frustum`.
+    pub fn from_euler<A: Into<Rad<f32>>>(pitch: A, yaw: A, roll: A) -> Transform {
+        Transform::from_rotation(Quaternion::from_angle_y(yaw) * Quaternion::from_angle_x(pitch) * Quaternion::from_angle_z(roll))
+    }
+
+    /// Splits a 4x4 matrix into translation, rotation, and scale. The matrix is assumed to be
+    /// composed as `T * R * S` with no shear or projection, like the ones produced by `to_matrix`.
+    /// Mirroring is represented as a negative x scale. Returns `None` if any axis has zero scale.
+    pub fn decompose(matrix: Matrix4<f32>) -> Option<Transform> {
+        let translation = matrix.w.truncate();
+        let (x, y, z) = (matrix.x.truncate(), matrix.y.truncate(), matrix.z.truncate());
+        let mut scale = Vector3::new(x.magnitude(), y.magnitude(), z.magnitude());
+        if scale.x < f32::EPSILON || scale.y < f32::EPSILON || scale.z < f32::EPSILON { return None }
+        let mut basis = Matrix3::from_cols(x / scale.x, y / scale.y, z / scale.z);
+        if basis.determinant() < 0.0 {
+            scale.x = -scale.x;
+            basis.x = -basis.x;
+        }
+        Some(Transform::from_trs(translation, Quaternion::from(basis), scale))
+    }
+
+    /// Returns a copy of this transform moved by the given offset.
+    pub fn translated(&self, offset: Vector3<f32>) -> Transform {
+        Transform { position: self.position + offset, ..self.clone() }
+    }
+
+    /// Returns a copy of this transform with the given rotation applied after the existing one.
+    pub fn rotated(&self, rotation: Quaternion<f32>) -> Transform {
+        Transform { rotation: rotation * self.rotation, ..self.clone() }
+    }
+
+    /// Returns a copy of this transform with its scale multiplied by the given scale.
+    pub fn scaled(&self, scale: Vector3<f32>) -> Transform {
+        Transform { scale: self.scale.mul_element_wise(scale), ..self.clone() }
+    }
+
+    /// Applies this transform to a point: scale, then rotation, then translation.
+    pub fn transform_point(&self, point: Point3<f32>) -> Point3<f32> {
+        self.position + self.rotation.rotate_vector(point.to_vec().mul_element_wise(self.scale))
+    }
+
+    /// Transforms an object-space AABB into the tightest AABB enclosing it after this transform.
+    pub fn transform_aabb(&self, aabb: &AABB) -> AABB {
+        AABB::from_points(&aabb.corners().map(|c| self.transform_point(c)))
+    }
+
+    /// Projects `point`, given in this transform's local space, to screen space: `x` and `y` are
+    /// pixel coordinates within `viewport` (with `y` increasing downwards) and `z` is depth, from 0
+    /// at the near plane to 1 at the far plane. `view_proj` is the camera's projection matrix
+    /// multiplied by its view matrix, with OpenGL-style clip space like cgmath's `perspective`.
+    /// Use an identity transform to project world-space points directly.
+    pub fn project(&self, point: Point3<f32>, view_proj: Matrix4<f32>, viewport: Rect<f32>) -> Point3<f32> {
+        let ndc = (view_proj * self.to_matrix()).transform_point(point);
+        Point3::new(
+            viewport.x + (ndc.x + 1.0) * 0.5 * viewport.w,
+            viewport.y + (1.0 - ndc.y) * 0.5 * viewport.h,
+            (ndc.z + 1.0) * 0.5,
+        )
+    }
+
+    /// The inverse of `project`: converts a screen-space point (pixel `x` and `y` within
+    /// `viewport`, and depth `z` in [0, 1]) back into this transform's local space. `inv_view_proj`
+    /// is the inverse of the matrix passed to `project`. If this transform can't be inverted (e.g.
+    /// it has a zero scale), the world-space point is returned instead.
+    pub fn unproject(&self, screen: Point3<f32>, inv_view_proj: Matrix4<f32>, viewport: Rect<f32>) -> Point3<f32> {
+        let ndc = Point3::new(
+            (screen.x - viewport.x) / viewport.w * 2.0 - 1.0,
+            1.0 - (screen.y - viewport.y) / viewport.h * 2.0,
+            screen.z * 2.0 - 1.0,
+        );
+        let world = inv_view_proj.transform_point(ndc);
+        match self.to_matrix().invert() {
+            Some(inverse) => inverse.transform_point(world),
+            None => world,
+        }
+    }
+
     /// Generates a 4x4 transformation matrix from this transform.
     pub fn to_matrix(&self) -> Matrix4<f32> {
+        #[cfg(test)]
+        MATRIX_COMPUTATIONS.with(|count| count.set(count.get() + 1));
         Matrix4::from_translation(self.position.to_vec())
             * Matrix4::from(self.rotation)
             * Matrix4::from_nonuniform_scale(self.scale.x, self.scale.y, self.scale.z)
     }
-}
\ No newline at end of file
+}
+
+impl Default for Transform {
+    /// Returns the identity transform.
+    fn default() -> Self { Transform::identity() }
+}
+
+impl Debug for Transform {
+    /// Prints the translation, rotation, and scale components.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Transform")
+            .field("translation", &self.position)
+            .field("rotation", &self.rotation)
+            .field("scale", &self.scale)
+            .finish()
+    }
+}
+
+/// A `Transform` that caches its generated matrix, for hot loops that read the matrix much more
+/// often than the transform changes. The transform is only reachable through accessors, so any
+/// mutable access marks the cache as dirty. This is kept separate from `Transform` so that plain
+/// transforms stay `Sync` and can still be built with struct literals.
+#[derive(Clone, Debug, Default)]
+pub struct CachedTransform {
+    transform: Transform,
+    /// The matrix generated from `transform`, or `None` if it needs to be regenerated.
+    matrix: Cell<Option<Matrix4<f32>>>,
+}
+
+impl CachedTransform {
+    pub fn new(transform: Transform) -> CachedTransform {
+        CachedTransform { transform, matrix: Cell::new(None) }
+    }
+
+    pub fn transform(&self) -> &Transform { &self.transform }
+
+    /// Returns the transform for modification, and invalidates the cached matrix.
+    pub fn transform_mut(&mut self) -> &mut Transform {
+        self.matrix.set(None);
+        &mut self.transform
+    }
+
+    pub fn set_transform(&mut self, transform: Transform) {
+        *self.transform_mut() = transform;
+    }
+
+    pub fn into_inner(self) -> Transform { self.transform }
+
+    /// Returns the 4x4 transformation matrix for the transform, only regenerating it if the
+    /// transform has been modified since the last call.
+    pub fn matrix(&self) -> Matrix4<f32> {
+        if let Some(matrix) = self.matrix.get() { return matrix }
+        let matrix = self.transform.to_matrix();
+        self.matrix.set(Some(matrix));
+        matrix
+    }
+
+    /// Applies the transform to a point using the cached matrix.
+    pub fn transform_point(&self, point: Point3<f32>) -> Point3<f32> {
+        self.matrix().transform_point(point)
+    }
+}
+
+impl From<Transform> for CachedTransform {
+    fn from(transform: Transform) -> Self { CachedTransform::new(transform) }
+}
+
+/// A 2D transform, with translation, rotation (in radians, counterclockwise), and scale. Mirrors
+/// `Transform` for 2D work like UI layout.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Transform2D {
+    pub translation: Vector2<f32>,
+    pub rotation: f32,
+    pub scale: Vector2<f32>,
+}
+
+impl Transform2D {
+    /// Creates an identity transform.
+    pub fn identity() -> Transform2D {
+        Transform2D {
+            translation: Vector2::new(0.0, 0.0),
+            rotation: 0.0,
+            scale: Vector2::new(1.0, 1.0),
+        }
+    }
+
+    /// Creates a transform from the given translation, rotation, and scale.
+    pub fn from_trs(translation: Vector2<f32>, rotation: f32, scale: Vector2<f32>) -> Transform2D {
+        Transform2D { translation, rotation, scale }
+    }
+
+    /// Applies this transform to a point: scale, then rotation, then translation.
+    pub fn transform_point(&self, point: Point2<f32>) -> Point2<f32> {
+        Point2::from_vec(self.rotate_vector(point.to_vec().mul_element_wise(self.scale)) + self.translation)
+    }
+
+    /// Generates a 3x3 homogeneous transformation matrix from this transform.
+    pub fn to_matrix3(&self) -> Matrix3<f32> {
+        let (sin, cos) = self.rotation.sin_cos();
+        let (sx, sy) = (self.scale.x, self.scale.y);
+        // column-major: rotated and scaled basis vectors, then the translation
+        Matrix3::new(
+            cos * sx, sin * sx, 0.0,
+            -sin * sy, cos * sy, 0.0,
+            self.translation.x, self.translation.y, 1.0,
+        )
+    }
+
+    /// Returns a transform equivalent to applying `other` first and then this transform, i.e. the
+    /// matrix product `self * other`. Like most TRS transforms, this is only exact if `self` has
+    /// a uniform scale; otherwise the result can't represent the resulting skew.
+    pub fn compose(&self, other: &Transform2D) -> Transform2D {
+        Transform2D {
+            translation: self.translation + self.rotate_vector(other.translation.mul_element_wise(self.scale)),
+            rotation: self.rotation + other.rotation,
+            scale: self.scale.mul_element_wise(other.scale),
+        }
+    }
+
+    /// Returns the transform that undoes this one. Only exact if the scale is uniform, for the same
+    /// reason as `compose`.
+    pub fn inverse(&self) -> Transform2D {
+        let scale = Vector2::new(1.0 / self.scale.x, 1.0 / self.scale.y);
+        let rotation = -self.rotation;
+        let translation = Basis2::from_angle(Rad(rotation)).rotate_vector(-self.translation).mul_element_wise(scale);
+        Transform2D { translation, rotation, scale }
+    }
+
+    fn rotate_vector(&self, v: Vector2<f32>) -> Vector2<f32> {
+        Basis2::from_angle(Rad(self.rotation)).rotate_vector(v)
+    }
+}
+
+impl Default for Transform2D {
+    /// Returns the identity transform.
+    fn default() -> Self { Transform2D::identity() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CachedTransform, Transform, Transform2D, MATRIX_COMPUTATIONS};
+    use crate::aabb::AABB;
+    use crate::rect::Rect;
+    use cgmath::{AbsDiffEq, Deg, InnerSpace, Matrix4, SquareMatrix, Point2, Point3, Quaternion, Rotation3, Vector2, Vector3};
+
+    #[test]
+    fn test_from_trs() {
+        let t = Vector3::new(1.0, 2.0, 3.0);
+        let r = Quaternion::from_angle_y(Deg(90.0));
+        let s = Vector3::new(2.0, 3.0, 4.0);
+        let expected = Matrix4::from_translation(t) * Matrix4::from(r) * Matrix4::from_nonuniform_scale(2.0, 3.0, 4.0);
+        assert!(Transform::from_trs(t, r, s).to_matrix().abs_diff_eq(&expected, 0.0001));
+        assert!(Transform::identity().translated(t).rotated(r).scaled(s).to_matrix().abs_diff_eq(&expected, 0.0001));
+    }
+
+    #[test]
+    fn test_from_euler_and_axis_angle() {
+        let (pitch, yaw, roll) = (Deg(30.0), Deg(-75.0), Deg(10.0));
+        let expected = Matrix4::from_angle_y(yaw) * Matrix4::from_angle_x(pitch) * Matrix4::from_angle_z(roll);
+        let transform = Transform::from_euler(pitch, yaw, roll);
+        assert!(transform.to_matrix().abs_diff_eq(&expected, 0.0001));
+        assert_eq!(transform.position, Point3::new(0.0, 0.0, 0.0));
+        assert_eq!(transform.scale, Vector3::new(1.0, 1.0, 1.0));
+
+        let axis_angle = Transform::from_axis_angle(Vector3::new(0.0, 2.0, 0.0), Deg(-75.0));
+        assert!(axis_angle.to_matrix().abs_diff_eq(&Matrix4::from_angle_y(Deg(-75.0)), 0.0001));
+        assert!(Transform::from_axis_angle(Vector3::new(1.0, 0.0, 0.0), Deg(30.0)).to_matrix()
+            .abs_diff_eq(&Transform::from_euler(Deg(30.0), Deg(0.0), Deg(0.0)).to_matrix(), 0.0001));
+    }
+
+    #[test]
+    fn test_decompose() {
+        let rotation = Transform::from_euler(Deg(30.0), Deg(-75.0), Deg(10.0));
+        let decomposed = Transform::decompose(rotation.to_matrix()).unwrap();
+        assert!(decomposed.to_matrix().abs_diff_eq(&rotation.to_matrix(), 0.0001));
+        assert!(decomposed.scale.abs_diff_eq(&Vector3::new(1.0, 1.0, 1.0), 0.0001));
+
+        let transform = Transform::from_trs(Vector3::new(1.0, -2.0, 3.0), rotation.rotation, Vector3::new(2.0, 0.5, 3.0));
+        let decomposed = Transform::decompose(transform.to_matrix()).unwrap();
+        assert!(decomposed.position.abs_diff_eq(&transform.position, 0.0001));
+        assert!(decomposed.scale.abs_diff_eq(&transform.scale, 0.0001));
+        // q and -q are the same rotation
+        assert!((decomposed.rotation.dot(transform.rotation).abs() - 1.0).abs() < 0.0001);
+
+        let mirrored = Transform::from_scale(Vector3::new(-1.0, 1.0, 1.0)).rotated(rotation.rotation);
+        let decomposed = Transform::decompose(mirrored.to_matrix()).unwrap();
+        assert!(decomposed.to_matrix().abs_diff_eq(&mirrored.to_matrix(), 0.0001));
+
+        assert!(Transform::decompose(Matrix4::from_nonuniform_scale(1.0, 0.0, 1.0)).is_none());
+    }
+
+    #[test]
+    fn test_project_unproject() {
+        let proj = cgmath::perspective(Deg(60.0), 16.0 / 9.0, 0.1, 100.0);
+        let view = Matrix4::look_at_rh(Point3::new(0.0, 2.0, 5.0), Point3::new(0.0, 0.0, 0.0), Vector3::unit_y());
+        let view_proj = proj * view;
+        let inv_view_proj = view_proj.invert().unwrap();
+        let viewport = Rect { x: 100.0, y: 50.0, w: 1600.0, h: 900.0 };
+
+        // the look-at target lands in the center of the viewport
+        let center = Transform::identity().project(Point3::new(0.0, 0.0, 0.0), view_proj, viewport);
+        assert!((center.x - 900.0).abs() < 0.01 && (center.y - 500.0).abs() < 0.01);
+        assert!(center.z > 0.0 && center.z < 1.0);
+        // up in the world is up on screen, which is decreasing y
+        assert!(Transform::identity().project(Point3::new(0.0, 0.5, 0.0), view_proj, viewport).y < center.y);
+
+        let transform = Transform::from_trs(Vector3::new(0.5, -0.25, 1.0), Quaternion::from_angle_y(Deg(30.0)), Vector3::new(2.0, 2.0, 2.0));
+        let point = Point3::new(0.3, 0.4, -0.2);
+        let screen = transform.project(point, view_proj, viewport);
+        assert!(viewport.test(screen.x, screen.y));
+        let back = transform.unproject(screen, inv_view_proj, viewport);
+        assert!(back.abs_diff_eq(&point, 0.001), "{:?} vs {:?}", back, point);
+    }
+
+    #[test]
+    fn test_builder_composition_order() {
+        let a = Quaternion::from_angle_x(Deg(90.0));
+        let b = Quaternion::from_angle_y(Deg(90.0));
+        let transform = Transform::identity().rotated(a).rotated(b);
+        // rotating by `a` then by `b` is the same as the matrix product B * A
+        assert!(transform.to_matrix().abs_diff_eq(&(Matrix4::from(b) * Matrix4::from(a)), 0.0001));
+
+        let moved = Transform::identity().translated(Vector3::new(1.0, 0.0, 0.0)).translated(Vector3::new(0.0, 2.0, 0.0));
+        assert_eq!(moved.to_matrix(), Matrix4::from_translation(Vector3::new(1.0, 2.0, 0.0)));
+    }
+
+    #[test]
+    fn test_transform_aabb() {
+        let aabb = AABB::from(Point3::new(-1.0, -2.0, -3.0), Point3::new(1.0, 2.0, 3.0));
+        let transform = Transform::from_trs(
+            Vector3::new(5.0, 0.0, -1.0),
+            Quaternion::from_angle_z(Deg(90.0)),
+            Vector3::new(2.0, 1.0, 1.0));
+        let world = transform.transform_aabb(&aabb);
+        let expected = aabb.transformed(transform.to_matrix());
+        for (a, b) in world.as_floats().iter().zip(expected.as_floats().iter()) {
+            assert!((a - b).abs() < 0.0001);
+        }
+        // x extent (2 * 2) is rotated onto y, y extent (2 * 2) onto x
+        assert!((world.size_x() - 4.0).abs() < 0.0001);
+        assert!((world.size_y() - 4.0).abs() < 0.0001);
+        assert!((world.size_z() - 6.0).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_default_and_debug() {
+        let p = Point3::new(1.5, -2.0, 3.25);
+        assert_eq!(Transform::default().transform_point(p), p);
+        assert_eq!(Transform::default().to_matrix(), Matrix4::from_scale(1.0));
+
+        let debug = format!("{:?}", Transform::from_position(p));
+        assert!(debug.starts_with("Transform { translation: "));
+        assert!(debug.contains("rotation: "));
+        assert!(debug.contains("scale: "));
+        assert!(!debug.contains("cache"));
+    }
+
+    #[test]
+    fn test_transform_is_sync() {
+        fn assert_sync<T: Sync>() {}
+        assert_sync::<Transform>();
+        let _literal = Transform { position: Point3::new(0.0, 0.0, 0.0), rotation: Quaternion::from_angle_y(Deg(0.0)), scale: Vector3::new(1.0, 1.0, 1.0) };
+    }
+
+    #[test]
+    fn test_matrix_cache_invalidation() {
+        let mut cached = CachedTransform::new(Transform::from_position(Point3::new(1.0, 2.0, 3.0)));
+        assert_eq!(cached.matrix(), Matrix4::from_translation(Vector3::new(1.0, 2.0, 3.0)));
+        cached.transform_mut().position.x = 5.0;
+        assert_eq!(cached.matrix(), Matrix4::from_translation(Vector3::new(5.0, 2.0, 3.0)));
+        cached.transform_mut().scale = Vector3::new(2.0, 2.0, 2.0);
+        assert_eq!(cached.matrix(), Matrix4::from_translation(Vector3::new(5.0, 2.0, 3.0)) * Matrix4::from_scale(2.0));
+        cached.set_transform(Transform::identity());
+        assert_eq!(cached.matrix(), Matrix4::from_scale(1.0));
+    }
+
+    #[test]
+    fn test_matrix_cache_reuse() {
+        let transform = Transform::from_trs(
+            Vector3::new(1.0, 2.0, 3.0),
+            Quaternion::from_angle_y(Deg(30.0)),
+            Vector3::new(1.0, 2.0, 1.0));
+        let computations = || MATRIX_COMPUTATIONS.with(|count| count.get());
+        let cached = CachedTransform::from(transform.clone());
+        let before = computations();
+        let first = cached.matrix();
+        for _ in 0..1000 {
+            assert_eq!(cached.matrix(), first);
+        }
+        assert_eq!(computations() - before, 1);
+        assert_eq!(first, transform.to_matrix());
+        let p = Point3::new(0.5, -1.0, 2.0);
+        assert!(cached.transform_point(p).abs_diff_eq(&transform.transform_point(p), 0.0001));
+        assert_eq!(computations() - before, 2);
+
+        let mut moved = cached.clone();
+        moved.transform_mut().position.x += 1.0;
+        moved.matrix();
+        moved.matrix();
+        assert_eq!(computations() - before, 3);
+    }
+
+    #[test]
+    fn test_transform_2d_matrix() {
+        let t = Transform2D::from_trs(Vector2::new(3.0, -1.0), std::f32::consts::FRAC_PI_2, Vector2::new(2.0, 1.0));
+        let p = Point2::new(1.0, 1.0);
+        // scaled to (2, 1), rotated to (-1, 2), translated to (2, 1)
+        assert!(t.transform_point(p).abs_diff_eq(&Point2::new(2.0, 1.0), 0.0001));
+        let by_matrix = t.to_matrix3() * Vector3::new(p.x, p.y, 1.0);
+        assert!(by_matrix.abs_diff_eq(&Vector3::new(2.0, 1.0, 1.0), 0.0001));
+    }
+
+    #[test]
+    fn test_transform_2d_compose_order() {
+        let a = Transform2D::from_trs(Vector2::new(1.0, 2.0), 0.5, Vector2::new(2.0, 2.0));
+        let b = Transform2D::from_trs(Vector2::new(-3.0, 0.5), 1.2, Vector2::new(1.0, 3.0));
+        let p = Point2::new(0.7, -1.4);
+        let ab = a.compose(&b);
+        assert!(ab.transform_point(p).abs_diff_eq(&a.transform_point(b.transform_point(p)), 0.0001));
+        assert!(ab.to_matrix3().abs_diff_eq(&(a.to_matrix3() * b.to_matrix3()), 0.0001));
+        assert!(!b.compose(&a).transform_point(p).abs_diff_eq(&ab.transform_point(p), 0.0001));
+    }
+
+    #[test]
+    fn test_transform_2d_inverse() {
+        let t = Transform2D::from_trs(Vector2::new(4.0, -2.0), 2.0, Vector2::new(0.5, 0.5));
+        let p = Point2::new(1.5, 7.0);
+        assert!(t.inverse().transform_point(t.transform_point(p)).abs_diff_eq(&p, 0.0001));
+        assert!(t.transform_point(t.inverse().transform_point(p)).abs_diff_eq(&p, 0.0001));
+        assert!(t.compose(&t.inverse()).to_matrix3().abs_diff_eq(&Transform2D::default().to_matrix3(), 0.0001));
+    }
+}